@@ -0,0 +1,370 @@
+//! Reproducible archive writing: the same inputs always produce the same bytes.
+
+use anyhow::{Context, Result};
+use flate2::{Compression, GzBuilder};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, System};
+
+use crate::config::ArchiveFormat;
+
+/// A file to pack, stored under `name` inside the archive.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub source: PathBuf,
+    pub executable: bool,
+}
+
+impl Entry {
+    pub(crate) fn mode(&self) -> u32 {
+        if self.executable { 0o755 } else { 0o644 }
+    }
+}
+
+/// Expands `[build] include` globs into entries named by their path relative to the project root.
+pub fn include_entries(patterns: &[String]) -> Result<Vec<Entry>> {
+    let mut entries = std::collections::BTreeMap::new();
+    for pattern in patterns {
+        let mut matched = false;
+        for path in glob::glob(pattern).with_context(|| format!("invalid include pattern {pattern:?}"))? {
+            let path = path?;
+            if !path.is_file() {
+                continue;
+            }
+            matched = true;
+            let name = entry_name(&path);
+            let executable = is_executable(&path)?;
+            entries.entry(name.clone()).or_insert(Entry {
+                name,
+                source: path,
+                executable,
+            });
+        }
+        if !matched {
+            eprintln!("[build] Warning: include pattern {pattern} matched no files");
+        }
+    }
+    Ok(entries.into_values().collect())
+}
+
+/// Relative paths keep their directories; anything outside the project root is stored by file name.
+fn entry_name(path: &Path) -> String {
+    let outside = path
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir));
+    if outside {
+        return path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    }
+    path.components()
+        .filter(|c| matches!(c, std::path::Component::Normal(_)))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(std::fs::metadata(path)?.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> Result<bool> {
+    Ok(false)
+}
+
+/// Timestamp stamped on every entry: `SOURCE_DATE_EPOCH`, else the HEAD commit time, else 0.
+pub fn source_date_epoch() -> u64 {
+    if let Some(epoch) = std::env::var("SOURCE_DATE_EPOCH").ok().and_then(|v| v.trim().parse().ok()) {
+        return epoch;
+    }
+    Command::new("git")
+        .args(["log", "-1", "--format=%ct"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+        .unwrap_or(0)
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarXz => "tar.xz",
+            ArchiveFormat::TarZst => "tar.zst",
+            ArchiveFormat::Zip => "zip",
+        }
+    }
+
+    /// Content type used when uploading the archive as a release asset
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "application/gzip",
+            ArchiveFormat::TarXz => "application/x-xz",
+            ArchiveFormat::TarZst => "application/zstd",
+            ArchiveFormat::Zip => "application/zip",
+        }
+    }
+}
+
+/// Release asset name for one target, e.g. `tool-1.0.0-x86_64-pc-windows-msvc.zip`
+pub fn archive_name(binary: &str, version: &str, target: &str, format: ArchiveFormat) -> String {
+    format!("{binary}-{version}-{target}.{}", format.extension())
+}
+
+/// Writes `entries` to `dest` with sorted entries, root ownership, fixed modes and
+/// `mtime` on every entry. Compressor headers carry no timestamps or file names.
+pub fn write_archive(dest: &Path, format: ArchiveFormat, entries: &[Entry], mtime: u64) -> Result<()> {
+    let file = std::fs::File::create(dest).with_context(|| format!("creating {}", dest.display()))?;
+    let written = match format {
+        ArchiveFormat::TarGz => {
+            let gz = GzBuilder::new().mtime(0).write(file, Compression::best());
+            write_tar(gz, entries, mtime).and_then(|gz| Ok(gz.finish()?))
+        }
+        ArchiveFormat::TarXz => {
+            let xz = xz2::write::XzEncoder::new(file, 9);
+            write_tar(xz, entries, mtime).and_then(|xz| Ok(xz.finish()?))
+        }
+        ArchiveFormat::TarZst => zstd::Encoder::new(file, 19)
+            .map_err(anyhow::Error::from)
+            .and_then(|zst| write_tar(zst, entries, mtime))
+            .and_then(|zst| Ok(zst.finish()?)),
+        ArchiveFormat::Zip => write_zip(file, entries, mtime),
+    };
+    written.map(drop).with_context(|| format!("writing {}", dest.display()))
+}
+
+fn write_tar<W: Write>(writer: W, entries: &[Entry], mtime: u64) -> Result<W> {
+    let mut sorted: Vec<&Entry> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut builder = tar::Builder::new(writer);
+    for entry in sorted {
+        let data = std::fs::read(&entry.source).with_context(|| format!("reading {}", entry.source.display()))?;
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(data.len() as u64);
+        header.set_mode(entry.mode());
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("")?;
+        header.set_groupname("")?;
+        builder.append_data(&mut header, &entry.name, &data[..])?;
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn write_zip(file: std::fs::File, entries: &[Entry], mtime: u64) -> Result<std::fs::File> {
+    let mut sorted: Vec<&Entry> = entries.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let modified = dos_date_time(mtime);
+    let mut zip = zip::ZipWriter::new(file);
+    for entry in sorted {
+        let data = std::fs::read(&entry.source).with_context(|| format!("reading {}", entry.source.display()))?;
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .system(System::Unix)
+            .unix_permissions(entry.mode())
+            .last_modified_time(modified);
+        zip.start_file(entry.name.as_str(), options)?;
+        zip.write_all(&data)?;
+    }
+    Ok(zip.finish()?)
+}
+
+/// Converts a Unix timestamp to the zip format's local date/time, clamped to its 1980–2107 range.
+fn dos_date_time(epoch: u64) -> zip::DateTime {
+    let days = (epoch / 86_400) as i64;
+    let secs = epoch % 86_400;
+    // civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    match year {
+        ..1980 => zip::DateTime::default(),
+        1980..=2107 => zip::DateTime::from_date_and_time(
+            year as u16,
+            month,
+            day,
+            (secs / 3600) as u8,
+            (secs / 60 % 60) as u8,
+            (secs % 60) as u8,
+        )
+        .unwrap_or_default(),
+        _ => zip::DateTime::from_date_and_time(2107, 12, 31, 23, 59, 58).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn fixture(dir: &Path) -> Vec<Entry> {
+        std::fs::write(dir.join("tool"), b"#!/bin/sh\necho hi\n").unwrap();
+        std::fs::write(dir.join("README.md"), b"# tool\n").unwrap();
+        vec![
+            Entry {
+                name: "tool".to_string(),
+                source: dir.join("tool"),
+                executable: true,
+            },
+            Entry {
+                name: "README.md".to_string(),
+                source: dir.join("README.md"),
+                executable: false,
+            },
+        ]
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("releasor2000-archive-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn tar_gz_is_byte_identical_across_builds() {
+        let dir = scratch_dir("identical");
+        let entries = fixture(&dir);
+        write_archive(&dir.join("a.tar.gz"), ArchiveFormat::TarGz, &entries, 1_700_000_000).unwrap();
+        // touching the source must not change the output
+        std::fs::write(dir.join("tool"), b"#!/bin/sh\necho hi\n").unwrap();
+        let reversed: Vec<Entry> = entries.iter().rev().cloned().collect();
+        write_archive(&dir.join("b.tar.gz"), ArchiveFormat::TarGz, &reversed, 1_700_000_000).unwrap();
+
+        let a = std::fs::read(dir.join("a.tar.gz")).unwrap();
+        let b = std::fs::read(dir.join("b.tar.gz")).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(a, b);
+        // gzip header mtime is zeroed
+        assert_eq!(&a[4..8], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn tar_gz_normalizes_metadata() {
+        let dir = scratch_dir("metadata");
+        let entries = fixture(&dir);
+        let dest = dir.join("out.tar.gz");
+        write_archive(&dest, ArchiveFormat::TarGz, &entries, 42).unwrap();
+
+        let file = std::fs::File::open(&dest).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        let mut seen = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let header = entry.header();
+            assert_eq!(header.mtime().unwrap(), 42);
+            assert_eq!(header.uid().unwrap(), 0);
+            assert_eq!(header.gid().unwrap(), 0);
+            assert_eq!(header.username().unwrap(), Some(""));
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            let mode = header.mode().unwrap();
+            let mut body = String::new();
+            entry.read_to_string(&mut body).unwrap();
+            seen.push((name, mode, body));
+        }
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            seen,
+            vec![
+                ("README.md".to_string(), 0o644, "# tool\n".to_string()),
+                ("tool".to_string(), 0o755, "#!/bin/sh\necho hi\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_format_is_reproducible() {
+        let dir = scratch_dir("formats");
+        let entries = fixture(&dir);
+        for format in [ArchiveFormat::TarXz, ArchiveFormat::TarZst, ArchiveFormat::Zip] {
+            let a = dir.join(format!("a.{}", format.extension()));
+            let b = dir.join(format!("b.{}", format.extension()));
+            write_archive(&a, format, &entries, 1_700_000_000).unwrap();
+            write_archive(&b, format, &entries, 1_700_000_000).unwrap();
+            assert_eq!(std::fs::read(&a).unwrap(), std::fs::read(&b).unwrap(), "{format:?}");
+        }
+
+        let xz = std::fs::File::open(dir.join("a.tar.xz")).unwrap();
+        let mut tar = tar::Archive::new(xz2::read::XzDecoder::new(xz));
+        assert_eq!(tar.entries().unwrap().count(), 2);
+        let zst = std::fs::File::open(dir.join("a.tar.zst")).unwrap();
+        let mut tar = tar::Archive::new(zstd::Decoder::new(zst).unwrap());
+        assert_eq!(tar.entries().unwrap().count(), 2);
+
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(dir.join("a.zip")).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(zip.file_names().count(), 2);
+        let tool = zip.by_name("tool").unwrap();
+        assert_eq!(tool.unix_mode(), Some(0o100755));
+        assert_eq!(tool.last_modified(), zip::DateTime::from_date_and_time(2023, 11, 14, 22, 13, 20).ok());
+    }
+
+    #[test]
+    fn entry_names_are_relative_to_the_project() {
+        assert_eq!(entry_name(Path::new("completions/tool.bash")), "completions/tool.bash");
+        assert_eq!(entry_name(Path::new("./LICENSE")), "LICENSE");
+        assert_eq!(entry_name(Path::new("../shared/LICENSE")), "LICENSE");
+        assert_eq!(entry_name(Path::new("/opt/docs/tool.1")), "tool.1");
+    }
+
+    #[test]
+    fn include_entries_expands_globs_and_skips_duplicates() {
+        let dir = scratch_dir("include");
+        std::fs::create_dir_all(dir.join("man/sub")).unwrap();
+        std::fs::write(dir.join("man/tool.1"), "").unwrap();
+        std::fs::write(dir.join("man/tool-sub.1"), "").unwrap();
+        let pattern = |p: &str| format!("{}/{p}", dir.display());
+        let entries = include_entries(&[pattern("man/*"), pattern("man/tool.1"), pattern("missing/*")]).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        // absolute patterns are stored by file name; directories are skipped
+        assert_eq!(names, vec!["tool-sub.1", "tool.1"]);
+        assert!(entries.iter().all(|e| !e.executable));
+    }
+
+    #[test]
+    fn dos_date_time_clamps_to_zip_range() {
+        assert_eq!(dos_date_time(0), zip::DateTime::default());
+        assert_eq!(
+            dos_date_time(951_782_400),
+            zip::DateTime::from_date_and_time(2000, 2, 29, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn archive_names_follow_format() {
+        assert_eq!(
+            archive_name("tool", "1.0.0", "x86_64-pc-windows-msvc", ArchiveFormat::Zip),
+            "tool-1.0.0-x86_64-pc-windows-msvc.zip"
+        );
+        assert_eq!(ArchiveFormat::TarZst.content_type(), "application/zstd");
+    }
+
+    #[test]
+    fn source_date_epoch_prefers_env() {
+        let saved = std::env::var("SOURCE_DATE_EPOCH").ok();
+        unsafe { std::env::set_var("SOURCE_DATE_EPOCH", "1234567890") };
+        assert_eq!(source_date_epoch(), 1234567890);
+        match saved {
+            Some(v) => unsafe { std::env::set_var("SOURCE_DATE_EPOCH", v) },
+            None => unsafe { std::env::remove_var("SOURCE_DATE_EPOCH") },
+        }
+    }
+}
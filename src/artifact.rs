@@ -0,0 +1,197 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256, Sha512};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::config::{ArchiveFormat, DigestAlgorithm};
+
+/// Hex digests of one file. SHA-256 is always computed; the others only when configured.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Digests {
+    pub sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha512: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blake3: Option<String>,
+}
+
+impl Digests {
+    pub fn get(&self, algorithm: DigestAlgorithm) -> Option<&str> {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Some(&self.sha256),
+            DigestAlgorithm::Sha512 => self.sha512.as_deref(),
+            DigestAlgorithm::Blake3 => self.blake3.as_deref(),
+        }
+    }
+}
+
+/// A built archive for one target, hashed once and shared by every channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub target: String,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub format: ArchiveFormat,
+    /// Extra files packed next to the binary, relative to the archive prefix
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    #[serde(flatten)]
+    pub digests: Digests,
+}
+
+impl Artifact {
+    pub fn new(
+        target: &str,
+        path: PathBuf,
+        format: ArchiveFormat,
+        files: Vec<String>,
+        algorithms: &[DigestAlgorithm],
+    ) -> Result<Self> {
+        let digests = digest_file(&path, algorithms)?;
+        Ok(Self {
+            target: target.to_string(),
+            path,
+            format,
+            files,
+            digests,
+        })
+    }
+
+    /// File name of the archive, which is also its release asset name.
+    pub fn name(&self) -> String {
+        self.path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+    }
+
+    pub fn sha256(&self) -> &str {
+        &self.digests.sha256
+    }
+}
+
+impl DigestAlgorithm {
+    /// Tag used in BSD-style checksum lines, e.g. `SHA256 (file) = ...`
+    pub fn label(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha256 => "SHA256",
+            DigestAlgorithm::Sha512 => "SHA512",
+            DigestAlgorithm::Blake3 => "BLAKE3",
+        }
+    }
+
+    /// File extension for per-file checksums, e.g. `archive.tar.gz.sha256`
+    pub fn extension(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha256 => "sha256",
+            DigestAlgorithm::Sha512 => "sha512",
+            DigestAlgorithm::Blake3 => "b3",
+        }
+    }
+
+    /// Conventional manifest name, matching `sha256sum`/`sha512sum`/`b3sum`
+    pub fn manifest_name(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha256 => "SHA256SUMS",
+            DigestAlgorithm::Sha512 => "SHA512SUMS",
+            DigestAlgorithm::Blake3 => "B3SUMS",
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Streams `reader` through SHA-256 and any extra `algorithms` in a single pass.
+pub fn digest_reader(mut reader: impl Read, algorithms: &[DigestAlgorithm]) -> Result<Digests> {
+    let mut sha256 = Sha256::new();
+    let mut sha512 = algorithms.contains(&DigestAlgorithm::Sha512).then(Sha512::new);
+    let mut blake3 = algorithms.contains(&DigestAlgorithm::Blake3).then(blake3::Hasher::new);
+
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        let chunk = &buf[..n];
+        sha256.update(chunk);
+        if let Some(h) = sha512.as_mut() {
+            h.update(chunk);
+        }
+        if let Some(h) = blake3.as_mut() {
+            h.update(chunk);
+        }
+    }
+
+    Ok(Digests {
+        sha256: hex(&sha256.finalize()),
+        sha512: sha512.map(|h| hex(&h.finalize())),
+        blake3: blake3.map(|h| h.finalize().to_hex().to_string()),
+    })
+}
+
+pub fn digest_file(path: &Path, algorithms: &[DigestAlgorithm]) -> Result<Digests> {
+    let file = std::fs::File::open(path).with_context(|| format!("opening {} for hashing", path.display()))?;
+    digest_reader(file, algorithms).with_context(|| format!("hashing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_reader_sha256_only_by_default() {
+        let digests = digest_reader(&b"abc"[..], &[]).unwrap();
+        assert_eq!(
+            digests.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(digests.sha512.is_none());
+        assert!(digests.blake3.is_none());
+    }
+
+    #[test]
+    fn digest_reader_extra_algorithms() {
+        let digests = digest_reader(&b"abc"[..], &[DigestAlgorithm::Sha512, DigestAlgorithm::Blake3]).unwrap();
+        assert_eq!(
+            digests.sha512.as_deref(),
+            Some(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            )
+        );
+        assert_eq!(
+            digests.blake3.as_deref(),
+            Some("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85")
+        );
+        assert_eq!(digests.get(DigestAlgorithm::Sha256), Some(digests.sha256.as_str()));
+    }
+
+    #[test]
+    fn digest_reader_spans_buffer_boundaries() {
+        let data = vec![7u8; 200 * 1024];
+        let streamed = digest_reader(&data[..], &[]).unwrap();
+        assert_eq!(streamed.sha256, hex(&Sha256::digest(&data)));
+    }
+
+    #[test]
+    fn artifact_serializes_digests_inline() {
+        let artifact = Artifact {
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            path: PathBuf::from("target/release-staging/tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz"),
+            format: ArchiveFormat::TarGz,
+            files: Vec::new(),
+            digests: Digests {
+                sha256: "abc".to_string(),
+                ..Default::default()
+            },
+        };
+        let json = serde_json::to_value(&artifact).unwrap();
+        assert_eq!(json["sha256"], "abc");
+        assert_eq!(json["format"], "tar.gz");
+        assert!(json.get("sha512").is_none());
+        assert!(json.get("files").is_none());
+        assert_eq!(artifact.name(), "tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz");
+        let back: Artifact = serde_json::from_value(json).unwrap();
+        assert_eq!(back, artifact);
+    }
+}
@@ -0,0 +1,278 @@
+use anyhow::{Context as _, Result};
+use md5::Md5;
+use sha2::{Digest as _, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, copy_hosted_packages, gzip, hosted_packages, run_cmd, update_hosted_repo,
+};
+use crate::archive::source_date_epoch;
+use crate::config::{AptRepoChannel, Config};
+use crate::package::{PackageFormat, read_deb_control};
+
+/// Maintains an APT repository of the `.deb` packages in a directory or on a branch.
+pub struct AptRepo;
+
+impl Channel for AptRepo {
+    fn name(&self) -> &str {
+        "apt-repo"
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.apt_repo.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, config: &Config) -> Vec<Requirement> {
+        let mut requirements = vec![Requirement::Command("git".to_string())];
+        if let Some(key) = config.channels.apt_repo.as_ref().and_then(|ch| ch.gpg_key.clone()) {
+            requirements.push(Requirement::Command("gpg".to_string()));
+            requirements.push(Requirement::GpgKey(key));
+        }
+        requirements
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_apt_repo(ctx)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_apt_repo(ctx)
+    }
+}
+
+/// Pool directory of a package, split by initial the way Debian does (`lib` packages by four letters).
+fn pool_dir(component: &str, package: &str) -> String {
+    let initial = match package.strip_prefix("lib") {
+        Some(rest) if !rest.is_empty() => &package[..4],
+        _ => &package[..1],
+    };
+    format!("pool/{component}/{initial}/{package}")
+}
+
+/// This release's `.deb` packages at their pool paths.
+fn new_packages(ctx: &Context) -> Vec<(String, PathBuf)> {
+    let ch = ctx.config.channels.apt_repo.as_ref().unwrap();
+    let name = ctx.config.packages.deb.as_ref().map(|d| d.package_name(&ctx.config.project)).unwrap_or_default();
+    ctx.packages
+        .iter()
+        .filter(|p| p.format == PackageFormat::Deb)
+        .map(|p| (format!("{}/{}", pool_dir(&ch.component, &name), p.name()), p.path.clone()))
+        .collect()
+}
+
+fn packages_stanza(control: &str, filename: &str, deb: &[u8]) -> String {
+    format!(
+        "{}\nFilename: {filename}\nSize: {}\nMD5sum: {:x}\nSHA256: {:x}\n",
+        control.trim_end(),
+        deb.len(),
+        Md5::digest(deb),
+        Sha256::digest(deb),
+    )
+}
+
+/// `Date:` format of a `Release` file, e.g. `Tue, 14 Nov 2023 22:13:20 UTC`.
+fn release_date(epoch: u64) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let (days, secs) = (epoch / 86400, epoch % 86400);
+    // civil-from-days, counting from 0000-03-01 so leap days fall at the end of each year
+    let z = days as i64 + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{}, {day:02} {} {year} {:02}:{:02}:{:02} UTC",
+        DAYS[(days % 7) as usize],
+        MONTHS[month as usize - 1],
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+    )
+}
+
+/// Renders `dists/<distribution>`: `Packages` and `Packages.gz` for every architecture in the
+/// pool, and the `Release` file listing their checksums.
+fn render_dists(ch: &AptRepoChannel, pool: &[(String, PathBuf)], date: u64) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut by_arch: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (filename, path) in pool {
+        let control = read_deb_control(path)?;
+        let arch = control
+            .lines()
+            .find_map(|l| l.strip_prefix("Architecture:"))
+            .with_context(|| format!("{} has no Architecture", path.display()))?
+            .trim()
+            .to_string();
+        let deb = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        by_arch.entry(arch).or_default().push(packages_stanza(&control, filename, &deb));
+    }
+
+    let mut indexes = Vec::new();
+    for (arch, stanzas) in &by_arch {
+        let packages = stanzas.join("\n");
+        let dir = format!("{}/binary-{arch}", ch.component);
+        indexes.push((format!("{dir}/Packages.gz"), gzip(packages.as_bytes())?));
+        indexes.push((format!("{dir}/Packages"), packages.into_bytes()));
+    }
+    indexes.sort();
+
+    let arches: Vec<&str> = by_arch.keys().map(String::as_str).collect();
+    let mut release = format!(
+        "Suite: {dist}\nCodename: {dist}\nDate: {}\nArchitectures: {}\nComponents: {}\n",
+        release_date(date),
+        arches.join(" "),
+        ch.component,
+        dist = ch.distribution,
+    );
+    release.push_str("MD5Sum:\n");
+    for (path, data) in &indexes {
+        release.push_str(&format!(" {:x} {} {path}\n", Md5::digest(data), data.len()));
+    }
+    release.push_str("SHA256:\n");
+    for (path, data) in &indexes {
+        release.push_str(&format!(" {:x} {} {path}\n", Sha256::digest(data), data.len()));
+    }
+
+    let dists = format!("dists/{}", ch.distribution);
+    let mut files: Vec<(String, Vec<u8>)> =
+        indexes.into_iter().map(|(path, data)| (format!("{dists}/{path}"), data)).collect();
+    files.push((format!("{dists}/Release"), release.into_bytes()));
+    Ok(files)
+}
+
+fn sign_args<'a>(key: &'a str, mode: &'a str, output: &'a str) -> Vec<&'a str> {
+    vec!["--batch", "--yes", "--local-user", key, "--armor", mode, "--output", output, "Release"]
+}
+
+fn release_apt_repo(ctx: &Context) -> Result<()> {
+    let ch = ctx.config.channels.apt_repo.as_ref().unwrap();
+    let new = new_packages(ctx);
+    if new.is_empty() {
+        println!("[apt-repo] No .deb packages were built, nothing to add");
+        return Ok(());
+    }
+    let remote = format!("https://github.com/{}.git", ctx.config.project.repo);
+    let message = format!("Add {} {} to the APT repository", ctx.config.project.name, ctx.version);
+    update_hosted_repo("apt-repo", &ch.location, &remote, "apt", &message, |root| {
+        copy_hosted_packages(root, &new)?;
+        let pool = hosted_packages(Some(root), "deb", &[])?;
+        let dists = root.join("dists").join(&ch.distribution);
+        for (path, data) in render_dists(ch, &pool, source_date_epoch())? {
+            let dest = root.join(path);
+            std::fs::create_dir_all(dest.parent().unwrap())?;
+            std::fs::write(&dest, data).with_context(|| format!("writing {}", dest.display()))?;
+        }
+        match &ch.gpg_key {
+            Some(key) => {
+                run_cmd("apt-repo", Some(&dists), "gpg", &sign_args(key, "--clearsign", "InRelease"))?;
+                run_cmd("apt-repo", Some(&dists), "gpg", &sign_args(key, "--detach-sign", "Release.gpg"))?;
+            }
+            None => {
+                // a stale signature would no longer match the new Release
+                for stale in ["InRelease", "Release.gpg"] {
+                    std::fs::remove_file(dists.join(stale)).ok();
+                }
+            }
+        }
+        println!("[apt-repo] Indexed {} package(s) in {}", pool.len(), root.display());
+        Ok(())
+    })
+}
+
+fn plan_apt_repo(ctx: &Context) -> Result<Plan> {
+    let ch = ctx.config.channels.apt_repo.as_ref().unwrap();
+    let new = new_packages(ctx);
+    let mut plan = Plan::new("apt-repo");
+    let root = ch.location.directory.as_deref().map(Path::new).filter(|d| d.exists());
+    let pool = hosted_packages(root, "deb", &new)?;
+    for (path, data) in render_dists(ch, &pool, source_date_epoch())? {
+        if !path.ends_with(".gz") {
+            plan.file(&path, String::from_utf8(data)?);
+        }
+    }
+    if let Some(key) = &ch.gpg_key {
+        plan.command("gpg", &sign_args(key, "--clearsign", "InRelease"));
+        plan.command("gpg", &sign_args(key, "--detach-sign", "Release.gpg"));
+    }
+    if let Some(branch) = &ch.location.branch {
+        plan.command("git", &["push", "origin", &format!("HEAD:refs/heads/{branch}")]);
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HostedRepo;
+    use crate::package::test_packages;
+
+    fn channel() -> AptRepoChannel {
+        AptRepoChannel {
+            enabled: true,
+            location: HostedRepo {
+                directory: Some("apt".to_string()),
+                branch: None,
+                path: None,
+            },
+            distribution: "stable".to_string(),
+            component: "main".to_string(),
+            gpg_key: None,
+        }
+    }
+
+    // --- render_dists tests ---
+
+    #[test]
+    fn pool_dir_splits_by_initial() {
+        assert_eq!(pool_dir("main", "tool"), "pool/main/t/tool");
+        assert_eq!(pool_dir("main", "libfoo"), "pool/main/libf/libfoo");
+        assert_eq!(pool_dir("main", "lib"), "pool/main/l/lib");
+    }
+
+    #[test]
+    fn release_date_formats_rfc2822() {
+        assert_eq!(release_date(0), "Thu, 01 Jan 1970 00:00:00 UTC");
+        assert_eq!(release_date(1_700_000_000), "Tue, 14 Nov 2023 22:13:20 UTC");
+        assert_eq!(release_date(951_782_400), "Tue, 29 Feb 2000 00:00:00 UTC");
+    }
+
+    #[test]
+    fn render_dists_indexes_every_version_in_the_pool() {
+        let dir = std::env::temp_dir().join(format!("releasor2000-apt-repo-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut pool = Vec::new();
+        for version in ["1.0.0", "1.1.0"] {
+            let deb = test_packages(&dir, version).into_iter().find(|p| p.format == PackageFormat::Deb).unwrap();
+            pool.push((format!("pool/main/t/tool/{}", deb.name()), deb.path));
+        }
+
+        let files = render_dists(&channel(), &pool, 1_700_000_000).unwrap();
+        let names: Vec<&str> = files.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "dists/stable/main/binary-amd64/Packages",
+                "dists/stable/main/binary-amd64/Packages.gz",
+                "dists/stable/Release"
+            ]
+        );
+        let packages = String::from_utf8(files[0].1.clone()).unwrap();
+        assert!(packages.starts_with("Package: tool\nVersion: 1.0.0\nArchitecture: amd64\n"));
+        assert!(packages.contains("\nFilename: pool/main/t/tool/tool_1.0.0_amd64.deb\nSize: "));
+        assert!(packages.contains("\n\nPackage: tool\nVersion: 1.1.0\n"));
+        assert_eq!(packages.matches("\nSHA256: ").count(), 2);
+
+        let release = String::from_utf8(files[2].1.clone()).unwrap();
+        assert!(release.starts_with(
+            "Suite: stable\nCodename: stable\nDate: Tue, 14 Nov 2023 22:13:20 UTC\nArchitectures: amd64\nComponents: main\nMD5Sum:\n"
+        ));
+        let sha = format!("{:x}", Sha256::digest(&files[0].1));
+        assert!(release.contains(&format!("SHA256:\n {sha} {} main/binary-amd64/Packages\n", files[0].1.len())));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
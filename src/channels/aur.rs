@@ -0,0 +1,409 @@
+use anyhow::{Result, bail};
+use std::path::Path;
+
+use super::{Channel, Context, Journal, Plan, Requirement, archive_prefix, run_cmd};
+use crate::artifact::Artifact;
+use crate::config::Config;
+use crate::extras::{Shell, completions, man_pages};
+
+/// Pushes a `PKGBUILD` and `.SRCINFO` for the Linux archives to an AUR `-bin` package.
+pub struct Aur;
+
+impl Channel for Aur {
+    fn name(&self) -> &str {
+        "aur"
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["github"]
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.aur.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![Requirement::Command("git".to_string())]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_aur(ctx.config, ctx.version, ctx.archives)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_aur(ctx.config, ctx.version, ctx.archives)
+    }
+}
+
+fn arch_linux_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("-linux-") {
+        None
+    } else if target.starts_with("x86_64") {
+        Some("x86_64")
+    } else if target.starts_with("aarch64") {
+        Some("aarch64")
+    } else {
+        None
+    }
+}
+
+/// `pkgver` may not contain hyphens.
+fn pkgver(version: &str) -> String {
+    version.replace('-', "_")
+}
+
+/// Everything the PKGBUILD and .SRCINFO are rendered from.
+struct Package<'a> {
+    name: &'a str,
+    /// Package the `-bin` package stands in for
+    provides: &'a str,
+    version: &'a str,
+    description: &'a str,
+    url: String,
+    license: &'a [String],
+    depends: &'a [String],
+    // (arch, download url, sha256, directory the archive unpacks into)
+    sources: Vec<(&'static str, String, &'a str, Option<String>)>,
+}
+
+fn quoted_list(items: &[impl AsRef<str>]) -> String {
+    items.iter().map(|i| format!("'{}'", i.as_ref())).collect::<Vec<_>>().join(" ")
+}
+
+fn package_lines(binaries: &[&str], files: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> =
+        binaries.iter().map(|b| format!("install -Dm755 {b} \"$pkgdir/usr/bin/{b}\"")).collect();
+    for (shell, path, command) in completions(files) {
+        let dest = match shell {
+            Shell::Bash => format!("/usr/share/bash-completion/completions/{command}"),
+            Shell::Zsh => format!("/usr/share/zsh/site-functions/_{command}"),
+            Shell::Fish => format!("/usr/share/fish/vendor_completions.d/{command}.fish"),
+        };
+        lines.push(format!("install -Dm644 {path} \"$pkgdir{dest}\""));
+    }
+    for (section, path) in man_pages(files) {
+        let name = Path::new(path).file_name().unwrap_or_default().to_string_lossy();
+        lines.push(format!("install -Dm644 {path} \"$pkgdir/usr/share/man/man{section}/{name}\""));
+    }
+    for path in files {
+        let name = Path::new(path).file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with("LICENSE") || name.starts_with("COPYING") {
+            lines.push(format!("install -Dm644 {path} \"$pkgdir/usr/share/licenses/$pkgname/{name}\""));
+        }
+    }
+    lines
+}
+
+fn generate_pkgbuild(pkg: &Package, maintainer: Option<&str>, binaries: &[&str], files: &[String]) -> String {
+    let mut out = String::new();
+    if let Some(maintainer) = maintainer {
+        out.push_str(&format!("# Maintainer: {maintainer}\n"));
+    }
+    let arches: Vec<&str> = pkg.sources.iter().map(|(arch, ..)| *arch).collect();
+    out.push_str(&format!("pkgname={}\n", pkg.name));
+    out.push_str(&format!("pkgver={}\n", pkgver(pkg.version)));
+    out.push_str("pkgrel=1\n");
+    out.push_str(&format!("pkgdesc=\"{}\"\n", pkg.description.replace('"', "\\\"")));
+    out.push_str(&format!("arch=({})\n", quoted_list(&arches)));
+    out.push_str(&format!("url=\"{}\"\n", pkg.url));
+    out.push_str(&format!("license=({})\n", quoted_list(pkg.license)));
+    if !pkg.depends.is_empty() {
+        out.push_str(&format!("depends=({})\n", quoted_list(pkg.depends)));
+    }
+    out.push_str(&format!("provides=('{}')\n", pkg.provides));
+    out.push_str(&format!("conflicts=('{}')\n", pkg.provides));
+    for (arch, url, sha256, _) in &pkg.sources {
+        out.push_str(&format!("source_{arch}=(\"{url}\")\n"));
+        out.push_str(&format!("sha256sums_{arch}=('{sha256}')\n"));
+    }
+
+    out.push_str("\npackage() {\n");
+    if pkg.sources.iter().any(|(.., prefix)| prefix.is_some()) {
+        out.push_str("  case \"$CARCH\" in\n");
+        for (arch, _, _, prefix) in &pkg.sources {
+            let dir = prefix.as_deref().unwrap_or(".");
+            out.push_str(&format!("    {arch}) cd \"$srcdir/{dir}\" ;;\n"));
+        }
+        out.push_str("  esac\n");
+    } else {
+        out.push_str("  cd \"$srcdir\"\n");
+    }
+    for line in package_lines(binaries, files) {
+        out.push_str(&format!("  {line}\n"));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Mirrors the field order of `makepkg --printsrcinfo`.
+fn generate_srcinfo(pkg: &Package) -> String {
+    let mut fields: Vec<(String, String)> = vec![
+        ("pkgdesc".into(), pkg.description.to_string()),
+        ("pkgver".into(), pkgver(pkg.version)),
+        ("pkgrel".into(), "1".into()),
+        ("url".into(), pkg.url.clone()),
+    ];
+    fields.extend(pkg.sources.iter().map(|(arch, ..)| ("arch".to_string(), arch.to_string())));
+    fields.extend(pkg.license.iter().map(|l| ("license".to_string(), l.clone())));
+    fields.extend(pkg.depends.iter().map(|d| ("depends".to_string(), d.clone())));
+    fields.push(("provides".into(), pkg.provides.to_string()));
+    fields.push(("conflicts".into(), pkg.provides.to_string()));
+    fields.extend(pkg.sources.iter().map(|(arch, url, ..)| (format!("source_{arch}"), url.clone())));
+    fields.extend(pkg.sources.iter().map(|(arch, _, sha256, _)| (format!("sha256sums_{arch}"), sha256.to_string())));
+
+    let mut out = format!("pkgbase = {}\n", pkg.name);
+    for (key, value) in fields {
+        out.push_str(&format!("\t{key} = {value}\n"));
+    }
+    out.push_str(&format!("\npkgname = {}\n", pkg.name));
+    out
+}
+
+/// Renders the PKGBUILD and .SRCINFO.
+fn render_package(config: &Config, version: &str, archives: &[Artifact]) -> Result<(String, String)> {
+    let ch = config.channels.aur.as_ref().unwrap();
+    let repo = &config.project.repo;
+    let name = ch.package_name(&config.project);
+
+    // the first archive per architecture wins, in the configured target order
+    let mut sources: Vec<(&str, String, &str, Option<String>)> = Vec::new();
+    for archive in archives {
+        let Some(arch) = arch_linux_arch(&archive.target) else { continue };
+        if sources.iter().any(|(a, ..)| *a == arch) {
+            continue;
+        }
+        let url = format!("https://github.com/{repo}/releases/download/v{version}/{}", archive.name());
+        sources.push((arch, url, archive.sha256(), archive_prefix(config, version, &archive.target)));
+    }
+    if sources.is_empty() {
+        bail!("[aur] no x86_64 or aarch64 Linux targets in build.targets");
+    }
+
+    let description = ch.description.clone().unwrap_or_else(|| config.project.name.clone());
+    let pkg = Package {
+        name: &name,
+        provides: name.strip_suffix("-bin").unwrap_or(&name),
+        version,
+        description: &description,
+        url: format!("https://github.com/{repo}"),
+        license: &ch.license,
+        depends: &ch.depends,
+        sources,
+    };
+    let files = archives.first().map(|a| a.files.as_slice()).unwrap_or_default();
+    let pkgbuild = generate_pkgbuild(&pkg, ch.maintainer.as_deref(), &config.project.binaries(), files);
+    Ok((pkgbuild, generate_srcinfo(&pkg)))
+}
+
+fn release_aur(config: &Config, version: &str, archives: &[Artifact]) -> Result<()> {
+    let ch = config.channels.aur.as_ref().unwrap();
+    let name = ch.package_name(&config.project);
+    let remote = ch.remote(&config.project);
+    let (pkgbuild, srcinfo) = render_package(config, version, archives)?;
+
+    let tmp_dir = std::env::temp_dir().join(format!("releasor2000-aur-{name}-{version}"));
+    if tmp_dir.exists() {
+        std::fs::remove_dir_all(&tmp_dir)?;
+    }
+    let tmp = tmp_dir.to_string_lossy();
+    run_cmd("aur", None, "git", &["clone", &remote, &tmp])?;
+    std::fs::write(tmp_dir.join("PKGBUILD"), pkgbuild)?;
+    std::fs::write(tmp_dir.join(".SRCINFO"), srcinfo)?;
+
+    let dir = Some(tmp_dir.as_path());
+    run_cmd("aur", dir, "git", &["add", "PKGBUILD", ".SRCINFO"])?;
+    if run_cmd("aur", dir, "git", &["status", "--porcelain"])?.is_empty() {
+        println!("[aur] {name} is already at {version}");
+    } else {
+        let message = format!("Update to {version}");
+        // commit as the maintainer when one is configured, otherwise as the local git user
+        let identity = ch.maintainer.as_deref().and_then(|m| m.split_once(" <")).map(|(name, email)| {
+            (format!("user.name={name}"), format!("user.email={}", email.trim_end_matches('>')))
+        });
+        let mut args = Vec::new();
+        if let Some((name, email)) = &identity {
+            args.extend(["-c", name.as_str(), "-c", email.as_str()]);
+        }
+        args.extend(["commit", "-m", message.as_str()]);
+        run_cmd("aur", dir, "git", &args)?;
+        // the AUR only accepts pushes to master
+        run_cmd("aur", dir, "git", &["push", "origin", "HEAD:master"])?;
+        println!("[aur] Pushed {name} {version} to {remote}");
+    }
+    std::fs::remove_dir_all(&tmp_dir).ok();
+    Ok(())
+}
+
+fn plan_aur(config: &Config, version: &str, archives: &[Artifact]) -> Result<Plan> {
+    let ch = config.channels.aur.as_ref().unwrap();
+    let remote = ch.remote(&config.project);
+    let (pkgbuild, srcinfo) = render_package(config, version, archives)?;
+
+    let mut plan = Plan::new("aur");
+    plan.file("PKGBUILD", pkgbuild);
+    plan.file(".SRCINFO", srcinfo);
+    plan.command("git", &["clone", &remote]);
+    plan.command("git", &["commit", "-m", &format!("Update to {version}")]);
+    plan.command("git", &["push", "origin", "HEAD:master"]);
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package<'a>(license: &'a [String], depends: &'a [String], prefix: Option<&str>) -> Package<'a> {
+        Package {
+            name: "tool-bin",
+            provides: "tool",
+            version: "1.2.3-rc.1",
+            description: "A \"tool\"",
+            url: "https://github.com/owner/repo".to_string(),
+            license,
+            depends,
+            sources: vec![
+                (
+                    "x86_64",
+                    "https://example.com/tool-x86_64.tar.gz".to_string(),
+                    "aaa",
+                    prefix.map(|p| format!("{p}-x86_64")),
+                ),
+                (
+                    "aarch64",
+                    "https://example.com/tool-aarch64.tar.gz".to_string(),
+                    "bbb",
+                    prefix.map(|p| format!("{p}-aarch64")),
+                ),
+            ],
+        }
+    }
+
+    // --- generate_pkgbuild tests ---
+
+    #[test]
+    fn generate_pkgbuild_lists_sources_per_arch() {
+        let license = vec!["MIT".to_string(), "Apache-2.0".to_string()];
+        let pkgbuild = generate_pkgbuild(&package(&license, &[], None), Some("Me <me@example.com>"), &["tool"], &[]);
+        assert!(pkgbuild.starts_with("# Maintainer: Me <me@example.com>\npkgname=tool-bin\npkgver=1.2.3_rc.1\npkgrel=1\n"));
+        assert!(pkgbuild.contains("pkgdesc=\"A \\\"tool\\\"\"\n"));
+        assert!(pkgbuild.contains("arch=('x86_64' 'aarch64')\n"));
+        assert!(pkgbuild.contains("license=('MIT' 'Apache-2.0')\n"));
+        assert!(pkgbuild.contains("provides=('tool')\nconflicts=('tool')\n"));
+        assert!(!pkgbuild.contains("depends="));
+        assert!(pkgbuild.contains("source_x86_64=(\"https://example.com/tool-x86_64.tar.gz\")\nsha256sums_x86_64=('aaa')\n"));
+        assert!(pkgbuild.contains("sha256sums_aarch64=('bbb')\n"));
+        assert!(pkgbuild.contains("  cd \"$srcdir\"\n  install -Dm755 tool \"$pkgdir/usr/bin/tool\"\n"));
+    }
+
+    #[test]
+    fn generate_pkgbuild_enters_archive_prefix_per_arch() {
+        let license = vec!["MIT".to_string()];
+        let depends = vec!["glibc".to_string()];
+        let pkgbuild = generate_pkgbuild(&package(&license, &depends, Some("tool-1.2.3")), None, &["tool"], &[]);
+        assert!(pkgbuild.starts_with("pkgname=tool-bin\n"));
+        assert!(pkgbuild.contains("depends=('glibc')\n"));
+        assert!(pkgbuild.contains("    x86_64) cd \"$srcdir/tool-1.2.3-x86_64\" ;;\n"));
+        assert!(pkgbuild.contains("    aarch64) cd \"$srcdir/tool-1.2.3-aarch64\" ;;\n"));
+    }
+
+    #[test]
+    fn package_lines_install_extra_files() {
+        let files = vec![
+            "LICENSE".to_string(),
+            "completions/tool.bash".to_string(),
+            "completions/_tool".to_string(),
+            "man/tool.1".to_string(),
+        ];
+        let lines = package_lines(&["tool", "toold"], &files);
+        assert_eq!(
+            lines,
+            vec![
+                "install -Dm755 tool \"$pkgdir/usr/bin/tool\"",
+                "install -Dm755 toold \"$pkgdir/usr/bin/toold\"",
+                "install -Dm644 completions/tool.bash \"$pkgdir/usr/share/bash-completion/completions/tool\"",
+                "install -Dm644 completions/_tool \"$pkgdir/usr/share/zsh/site-functions/_tool\"",
+                "install -Dm644 man/tool.1 \"$pkgdir/usr/share/man/man1/tool.1\"",
+                "install -Dm644 LICENSE \"$pkgdir/usr/share/licenses/$pkgname/LICENSE\"",
+            ]
+        );
+    }
+
+    // --- generate_srcinfo tests ---
+
+    #[test]
+    fn generate_srcinfo_matches_makepkg_layout() {
+        let license = vec!["MIT".to_string()];
+        let srcinfo = generate_srcinfo(&package(&license, &[], None));
+        assert_eq!(
+            srcinfo,
+            "pkgbase = tool-bin\n\
+             \tpkgdesc = A \"tool\"\n\
+             \tpkgver = 1.2.3_rc.1\n\
+             \tpkgrel = 1\n\
+             \turl = https://github.com/owner/repo\n\
+             \tarch = x86_64\n\
+             \tarch = aarch64\n\
+             \tlicense = MIT\n\
+             \tprovides = tool\n\
+             \tconflicts = tool\n\
+             \tsource_x86_64 = https://example.com/tool-x86_64.tar.gz\n\
+             \tsource_aarch64 = https://example.com/tool-aarch64.tar.gz\n\
+             \tsha256sums_x86_64 = aaa\n\
+             \tsha256sums_aarch64 = bbb\n\
+             \n\
+             pkgname = tool-bin\n"
+        );
+    }
+
+    #[test]
+    fn arch_linux_arch_maps_linux_targets() {
+        assert_eq!(arch_linux_arch("x86_64-unknown-linux-gnu"), Some("x86_64"));
+        assert_eq!(arch_linux_arch("aarch64-unknown-linux-musl"), Some("aarch64"));
+        assert_eq!(arch_linux_arch("x86_64-apple-darwin"), None);
+        assert_eq!(arch_linux_arch("armv7-unknown-linux-gnueabihf"), None);
+    }
+
+    // --- release_aur tests ---
+
+    #[test]
+    fn release_aur_commits_once_per_version_to_bare_repo() {
+        let dir = std::env::temp_dir().join(format!("releasor2000-aur-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let remote = dir.join("tool-bin.git");
+        run_cmd("aur", None, "git", &["init", "--bare", "-q", &remote.to_string_lossy()]).unwrap();
+        let archive_path = dir.join("tool-1.2.3-x86_64-unknown-linux-gnu.tar.gz");
+        std::fs::write(&archive_path, "archive").unwrap();
+        let archives = vec![
+            Artifact::new("x86_64-unknown-linux-gnu", archive_path, Default::default(), Vec::new(), &[]).unwrap(),
+        ];
+        let toml = format!(
+            r#"
+[project]
+name = "tool"
+repo = "owner/tool"
+
+[build]
+pre_built_dir = "dist"
+targets = ["x86_64-unknown-linux-gnu"]
+
+[channels.aur]
+license = ["MIT"]
+remote = "{}"
+maintainer = "Tool Maintainer <tool@example.com>"
+"#,
+            remote.display()
+        );
+        let config = Config::parse(&toml).unwrap();
+
+        release_aur(&config, "1.2.3", &archives).unwrap();
+        // a second run for the same version has nothing to commit
+        release_aur(&config, "1.2.3", &archives).unwrap();
+
+        let git_dir = format!("--git-dir={}", remote.display());
+        let log = run_cmd("aur", None, "git", &[&git_dir, "log", "--format=%an <%ae> %s", "master"]).unwrap();
+        assert_eq!(log, "Tool Maintainer <tool@example.com> Update to 1.2.3");
+        let srcinfo = run_cmd("aur", None, "git", &[&git_dir, "show", "master:.SRCINFO"]).unwrap();
+        assert!(srcinfo.starts_with("pkgbase = tool-bin\n"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
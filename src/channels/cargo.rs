@@ -0,0 +1,43 @@
+use anyhow::Result;
+
+use super::{Channel, Context, Journal, Plan, Requirement, run_cmd};
+use crate::config::Config;
+
+/// Publishes the crate to crates.io.
+pub struct Cargo;
+
+impl Channel for Cargo {
+    fn name(&self) -> &str {
+        "cargo"
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.cargo.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![Requirement::Command("cargo".to_string())]
+    }
+
+    fn plan(&self, _ctx: &Context) -> Result<Plan> {
+        Ok(plan_cargo())
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_cargo(ctx.config)
+    }
+}
+
+fn release_cargo(config: &Config) -> Result<()> {
+    let ch = config.channels.cargo.as_ref().unwrap();
+    let crate_name = ch.crate_name.as_deref().unwrap_or(&config.project.name);
+    run_cmd("cargo", None, "cargo", &["publish"])?;
+    println!("[cargo] Published crate {crate_name}");
+    Ok(())
+}
+
+fn plan_cargo() -> Plan {
+    let mut plan = Plan::new("cargo");
+    plan.command("cargo", &["publish"]);
+    plan
+}
@@ -0,0 +1,522 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, github_token_requirement, is_windows, staging_dir, substitute,
+};
+use crate::config::{ArchiveFormat, Config, Signing, SigningMethod};
+use crate::github;
+
+/// Uploads an `install.sh` (and `install.ps1` for Windows targets) that fetches the right archive for the host.
+pub struct Curl;
+
+impl Channel for Curl {
+    fn name(&self) -> &str {
+        "curl"
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["github"]
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.curl.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![github_token_requirement()]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        Ok(plan_curl(ctx))
+    }
+
+    fn publish(&self, ctx: &Context, journal: &mut Journal) -> Result<()> {
+        release_curl(ctx, journal)
+    }
+}
+
+/// Every install script for this release as `(file name, contents)`.
+pub(super) fn render_install_scripts(ctx: &Context) -> Vec<(&'static str, String)> {
+    let mut scripts = vec![("install.sh", render_install_script(ctx))];
+    if let Some(ps1) = render_install_ps1(ctx) {
+        scripts.push(("install.ps1", ps1));
+    }
+    scripts
+}
+
+/// Renders the install scripts into the version's staging directory and returns their paths.
+fn write_install_scripts(ctx: &Context) -> Result<Vec<PathBuf>> {
+    let dir = staging_dir(ctx.version);
+    std::fs::create_dir_all(&dir)?;
+    let mut paths = Vec::new();
+    for (name, script) in render_install_scripts(ctx) {
+        let script_path = dir.join(name);
+        std::fs::write(&script_path, &script)?;
+        paths.push(script_path);
+    }
+    Ok(paths)
+}
+
+fn release_curl(ctx: &Context, journal: &mut Journal) -> Result<()> {
+    let (repo, version) = (&ctx.config.project.repo, ctx.version);
+    let script_paths = write_install_scripts(ctx)?;
+
+    let upload_url = match journal.upload_url.clone() {
+        Some(url) => url,
+        None => {
+            // Get the release to find its upload URL
+            let url = format!("https://api.github.com/repos/{repo}/releases/tags/v{version}");
+            let resp = github::api("curl", "GET", &url, None)?;
+            let upload_url = resp["upload_url"].as_str().ok_or_else(|| {
+                anyhow::anyhow!("[curl] could not find release v{version} — is the github channel enabled?")
+            })?;
+            let upload_url = upload_url.split('{').next().unwrap_or(upload_url).to_string();
+            journal.upload_url = Some(upload_url.clone());
+            journal.save()?;
+            upload_url
+        }
+    };
+
+    for script_path in &script_paths {
+        let name = script_path.file_name().unwrap_or_default().to_string_lossy();
+        let step = format!("curl/{name}");
+        if journal.is_done(&step) {
+            println!("[curl] {name} already uploaded, skipping");
+            continue;
+        }
+        github::upload_asset("curl", &upload_url, script_path, &name, "text/plain")?;
+        journal.complete(&step)?;
+        println!("[curl] Uploaded {name} to release v{version}");
+    }
+    Ok(())
+}
+
+fn render_install_script(ctx: &Context) -> String {
+    let config = ctx.config;
+    let formats: Vec<(&str, ArchiveFormat)> = ctx
+        .archives
+        .iter()
+        .filter(|a| !is_windows(&a.target))
+        .map(|a| (a.target.as_str(), a.format))
+        .collect();
+    let prefix = config.build.archive_prefix.as_deref();
+    let verify = config.channels.curl.as_ref().filter(|ch| ch.verify_signatures).and(config.signing.as_ref());
+    generate_install_script(&config.project.binaries(), &config.project.repo, ctx.version, &formats, prefix, verify)
+}
+
+/// Quotes `s` for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Lines of `install.sh` that download the archive's signature and check it against the public key.
+fn verify_lines(signing: &Signing) -> String {
+    let public_key = shell_quote(signing.public_key.as_deref().unwrap_or_default());
+    let (tool, extension, check) = match signing.method {
+        SigningMethod::Minisign => (
+            "minisign",
+            "minisig",
+            format!("minisign -Vqm \"$TMPDIR/archive\" -x \"$TMPDIR/archive.minisig\" -P {public_key}"),
+        ),
+        SigningMethod::Ssh => (
+            "ssh-keygen",
+            "sig",
+            format!(
+                "printf '%s %s\\n' \"$REPO\" {public_key} > \"$TMPDIR/allowed_signers\"\nssh-keygen -Y verify -f \"$TMPDIR/allowed_signers\" -I \"$REPO\" -n {} -s \"$TMPDIR/archive.sig\" < \"$TMPDIR/archive\" >/dev/null",
+                shell_quote(&signing.namespace)
+            ),
+        ),
+        // a throwaway keyring holding only the release key
+        SigningMethod::Gpg => (
+            "gpg",
+            "asc",
+            format!(
+                "mkdir -m 700 \"$TMPDIR/gnupg\"\nprintf '%s\\n' {public_key} | gpg --homedir \"$TMPDIR/gnupg\" --batch --quiet --import\ngpg --homedir \"$TMPDIR/gnupg\" --batch --quiet --trust-model always --verify \"$TMPDIR/archive.asc\" \"$TMPDIR/archive\" 2>/dev/null"
+            ),
+        ),
+    };
+    format!(
+        r#"if ! command -v {tool} >/dev/null 2>&1; then
+  echo "{tool} is required to verify the download" >&2
+  exit 1
+fi
+curl -fsSL "$URL.{extension}" -o "$TMPDIR/archive.{extension}"
+{check} || {{ echo "Signature check failed for $URL" >&2; exit 1; }}
+echo "Verified the signature of $BINARY v$VERSION"
+"#
+    )
+}
+
+/// The first of `binaries` names the archives. `formats` maps each built target to its
+/// archive format; `prefix` is the `[build] archive_prefix` template, resolved by the script.
+/// With `verify`, the archive's signature is checked before it is unpacked.
+fn generate_install_script(
+    binaries: &[&str],
+    repo: &str,
+    version: &str,
+    formats: &[(&str, ArchiveFormat)],
+    prefix: Option<&str>,
+    verify: Option<&Signing>,
+) -> String {
+    let binary = binaries.first().copied().unwrap_or_default();
+    let all_binaries = binaries.join(" ");
+    let shell_vars = &[("binary", "${BINARY}"), ("version", "${VERSION}"), ("target", "${TARGET}")];
+    let unpacked = match prefix.map(|p| substitute(p, shell_vars)) {
+        Some(p) if !p.trim_matches('/').is_empty() => format!("$TMPDIR/{}", p.trim_matches('/')),
+        _ => "$TMPDIR".to_string(),
+    };
+    let format_cases: String = formats
+        .iter()
+        .map(|(target, format)| format!("  {target}) FORMAT=\"{}\" ;;\n", format.extension()))
+        .collect();
+    let verify = verify.map(verify_lines).unwrap_or_default();
+    format!(
+        r#"#!/bin/sh
+set -eu
+
+BINARY="{binary}"
+BINARIES="{all_binaries}"
+REPO="{repo}"
+VERSION="{version}"
+
+OS="$(uname -s)"
+ARCH="$(uname -m)"
+
+case "$OS" in
+  Linux)  OS_TARGET="unknown-linux-gnu" ;;
+  Darwin) OS_TARGET="apple-darwin" ;;
+  *)      echo "Unsupported OS: $OS" >&2; exit 1 ;;
+esac
+
+case "$ARCH" in
+  x86_64|amd64) ARCH_TARGET="x86_64" ;;
+  arm64|aarch64) ARCH_TARGET="aarch64" ;;
+  *)             echo "Unsupported architecture: $ARCH" >&2; exit 1 ;;
+esac
+
+TARGET="${{ARCH_TARGET}}-${{OS_TARGET}}"
+case "$TARGET" in
+{format_cases}  *) echo "No prebuilt $BINARY archive for $TARGET" >&2; exit 1 ;;
+esac
+URL="https://github.com/${{REPO}}/releases/download/v${{VERSION}}/${{BINARY}}-${{VERSION}}-${{TARGET}}.${{FORMAT}}"
+
+TMPDIR="$(mktemp -d)"
+trap 'rm -rf "$TMPDIR"' EXIT
+
+echo "Downloading $BINARY v$VERSION for $TARGET..."
+curl -fsSL "$URL" -o "$TMPDIR/archive"
+{verify}case "$FORMAT" in
+  zip)     unzip -q "$TMPDIR/archive" -d "$TMPDIR" ;;
+  tar.zst) zstd -dc "$TMPDIR/archive" | tar x -C "$TMPDIR" ;;
+  *)       tar xf "$TMPDIR/archive" -C "$TMPDIR" ;;
+esac
+
+if [ -z "${{INSTALL_DIR:-}}" ]; then
+  printf "Install directory [/usr/local/bin]: "
+  read -r INSTALL_DIR
+  INSTALL_DIR="${{INSTALL_DIR:-/usr/local/bin}}"
+fi
+install -d "$INSTALL_DIR"
+for BIN in $BINARIES; do
+  install "{unpacked}/$BIN" "$INSTALL_DIR/$BIN"
+  echo "Installed $BIN to $INSTALL_DIR/$BIN"
+done
+"#
+    )
+}
+
+fn render_install_ps1(ctx: &Context) -> Option<String> {
+    let config = ctx.config;
+    let targets: Vec<(&str, ArchiveFormat)> = ctx
+        .archives
+        .iter()
+        .filter(|a| is_windows(&a.target))
+        .map(|a| (a.target.as_str(), a.format))
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+    let prefix = config.build.archive_prefix.as_deref();
+    Some(generate_install_ps1(&config.project.binaries(), &config.project.repo, ctx.version, &targets, prefix))
+}
+
+/// PowerShell counterpart of [`generate_install_script`] for Windows `targets`.
+fn generate_install_ps1(
+    binaries: &[&str],
+    repo: &str,
+    version: &str,
+    targets: &[(&str, ArchiveFormat)],
+    prefix: Option<&str>,
+) -> String {
+    let binary = binaries.first().copied().unwrap_or_default();
+    let all_binaries: Vec<String> = binaries.iter().map(|b| format!("'{b}'")).collect();
+    let ps_vars = &[("binary", "${Binary}"), ("version", "${Version}"), ("target", "${Target}")];
+    let unpacked = match prefix.map(|p| substitute(p, ps_vars)) {
+        Some(p) if !p.trim_matches('/').is_empty() => format!("Join-Path $Tmp \"{}\"", p.trim_matches('/')),
+        _ => "$Tmp".to_string(),
+    };
+
+    // first built target per architecture, e.g. msvc over gnu when both are built
+    let mut arch_cases = Vec::new();
+    for arch in ["x86_64", "aarch64", "i686"] {
+        if let Some((target, format)) = targets.iter().find(|(t, _)| t.starts_with(&format!("{arch}-"))) {
+            arch_cases.push(format!(
+                "    '{arch}' {{ $Target = '{target}'; $Format = '{}' }}",
+                format.extension()
+            ));
+        }
+    }
+
+    let template = r#"# Installs BINARY vVERSION from https://github.com/REPO
+$ErrorActionPreference = 'Stop'
+
+$Binary = 'BINARY'
+$Binaries = @(ALLBINARIES)
+$Repo = 'REPO'
+$Version = 'VERSION'
+
+$Arch = switch ($env:PROCESSOR_ARCHITECTURE) {
+    'AMD64' { 'x86_64' }
+    'ARM64' { 'aarch64' }
+    'x86'   { 'i686' }
+    default { throw "Unsupported architecture: $env:PROCESSOR_ARCHITECTURE" }
+}
+
+switch ($Arch) {
+ARCHCASES
+    default { throw "No prebuilt $Binary archive for $Arch Windows" }
+}
+$Url = "https://github.com/$Repo/releases/download/v$Version/${Binary}-${Version}-${Target}.$Format"
+
+$Tmp = Join-Path ([System.IO.Path]::GetTempPath()) ([System.IO.Path]::GetRandomFileName())
+New-Item -ItemType Directory -Path $Tmp | Out-Null
+try {
+    $Archive = Join-Path $Tmp "archive.$Format"
+    Write-Host "Downloading $Binary v$Version for $Target..."
+    Invoke-WebRequest -Uri $Url -OutFile $Archive -UseBasicParsing
+    if ($Format -eq 'zip') {
+        Expand-Archive -Path $Archive -DestinationPath $Tmp -Force
+    } else {
+        tar -xf $Archive -C $Tmp
+    }
+    $Unpacked = UNPACKED
+
+    $InstallDir = if ($env:INSTALL_DIR) { $env:INSTALL_DIR } else { Join-Path $env:LOCALAPPDATA "Programs\$Binary\bin" }
+    New-Item -ItemType Directory -Force -Path $InstallDir | Out-Null
+    foreach ($Bin in $Binaries) {
+        Copy-Item -Path (Join-Path $Unpacked "$Bin.exe") -Destination $InstallDir -Force
+        Write-Host "Installed $Bin to $InstallDir\$Bin.exe"
+    }
+
+    $UserPath = [Environment]::GetEnvironmentVariable('Path', 'User')
+    if (($UserPath -split ';') -notcontains $InstallDir) {
+        $NewPath = if ($UserPath) { "$UserPath;$InstallDir" } else { $InstallDir }
+        [Environment]::SetEnvironmentVariable('Path', $NewPath, 'User')
+        Write-Host "Added $InstallDir to your PATH, restart your terminal to pick it up"
+    }
+} finally {
+    Remove-Item -Recurse -Force $Tmp
+}
+"#;
+
+    template
+        .replace("ALLBINARIES", &all_binaries.join(", "))
+        .replace("ARCHCASES", &arch_cases.join("\n"))
+        .replace("UNPACKED", &unpacked)
+        .replace("BINARY", binary)
+        .replace("REPO", repo)
+        .replace("VERSION", version)
+}
+
+fn plan_curl(ctx: &Context) -> Plan {
+    let mut plan = Plan::new("curl");
+    for (name, script) in render_install_scripts(ctx) {
+        let script_path = plan.file(name, script);
+        plan.upload(&ctx.config.project.repo, script_path, "text/plain");
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- generate_install_script tests ---
+
+    #[test]
+    fn generate_install_script_starts_with_shebang() {
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, None);
+        assert!(script.starts_with("#!/bin/sh"));
+    }
+
+    #[test]
+    fn generate_install_script_contains_repo_binary_version() {
+        let script = generate_install_script(&["mytool"], "cool/repo", "3.2.1", &[], None, None);
+        assert!(script.contains("BINARY=\"mytool\""));
+        assert!(script.contains("REPO=\"cool/repo\""));
+        assert!(script.contains("VERSION=\"3.2.1\""));
+    }
+
+    #[test]
+    fn generate_install_script_installs_every_binary() {
+        let script = generate_install_script(&["mytool", "mytool-daemon"], "cool/repo", "3.2.1", &[], None, None);
+        assert!(script.contains("BINARY=\"mytool\""));
+        assert!(script.contains("BINARIES=\"mytool mytool-daemon\""));
+        assert!(script.contains("for BIN in $BINARIES; do"));
+    }
+
+    #[test]
+    fn generate_install_script_handles_all_arch_os_combos() {
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, None);
+        assert!(script.contains("Linux)"));
+        assert!(script.contains("Darwin)"));
+        assert!(script.contains("x86_64|amd64)"));
+        assert!(script.contains("arm64|aarch64)"));
+    }
+
+    #[test]
+    fn generate_install_script_prompts_for_install_dir() {
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, None);
+        assert!(script.contains("printf \"Install directory [/usr/local/bin]: \""));
+        assert!(script.contains("read -r INSTALL_DIR"));
+    }
+
+    #[test]
+    fn generate_install_script_picks_format_per_target() {
+        let script = generate_install_script(
+            &["tool"],
+            "owner/repo",
+            "1.0.0",
+            &[
+                ("x86_64-unknown-linux-gnu", ArchiveFormat::TarZst),
+                ("aarch64-apple-darwin", ArchiveFormat::TarGz),
+            ],
+            None,
+            None,
+        );
+        assert!(script.contains("  x86_64-unknown-linux-gnu) FORMAT=\"tar.zst\" ;;\n"));
+        assert!(script.contains("  aarch64-apple-darwin) FORMAT=\"tar.gz\" ;;\n"));
+        assert!(script.contains("${BINARY}-${VERSION}-${TARGET}.${FORMAT}"));
+        assert!(script.contains("zstd -dc"));
+        assert!(script.contains("install \"$TMPDIR/$BIN\" \"$INSTALL_DIR/$BIN\""));
+    }
+
+    #[test]
+    fn generate_install_script_looks_inside_archive_prefix() {
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], Some("{binary}-{version}-{target}/"), None);
+        assert!(script.contains("install \"$TMPDIR/${BINARY}-${VERSION}-${TARGET}/$BIN\" \"$INSTALL_DIR/$BIN\""));
+    }
+
+    fn signing(method: SigningMethod, public_key: &str) -> Signing {
+        Signing {
+            method,
+            key: "release.key".to_string(),
+            public_key: Some(public_key.to_string()),
+            namespace: "file".to_string(),
+        }
+    }
+
+    #[test]
+    fn generate_install_script_verifies_minisign_signature() {
+        let signing = signing(SigningMethod::Minisign, "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3");
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, Some(&signing));
+        assert!(script.contains(
+            "curl -fsSL \"$URL\" -o \"$TMPDIR/archive\"\nif ! command -v minisign >/dev/null 2>&1; then\n"
+        ));
+        assert!(script.contains("curl -fsSL \"$URL.minisig\" -o \"$TMPDIR/archive.minisig\"\n"));
+        assert!(script.contains(
+            "minisign -Vqm \"$TMPDIR/archive\" -x \"$TMPDIR/archive.minisig\" -P 'RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3' || {"
+        ));
+        assert!(script.find("minisign -Vqm").unwrap() < script.find("case \"$FORMAT\" in").unwrap());
+    }
+
+    #[test]
+    fn generate_install_script_verifies_ssh_signature() {
+        let signing = signing(SigningMethod::Ssh, "ssh-ed25519 AAAAC3Nza me's key");
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, Some(&signing));
+        assert!(script.contains("curl -fsSL \"$URL.sig\" -o \"$TMPDIR/archive.sig\"\n"));
+        assert!(script.contains("printf '%s %s\\n' \"$REPO\" 'ssh-ed25519 AAAAC3Nza me'\\''s key' > \"$TMPDIR/allowed_signers\"\n"));
+        assert!(script.contains("ssh-keygen -Y verify -f \"$TMPDIR/allowed_signers\" -I \"$REPO\" -n 'file' -s \"$TMPDIR/archive.sig\""));
+        assert!(!generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, None).contains("archive.sig"));
+    }
+
+    #[test]
+    fn generate_install_script_verifies_gpg_signature_with_its_own_keyring() {
+        let key = "-----BEGIN PGP PUBLIC KEY BLOCK-----\n\nmQENBGk\n-----END PGP PUBLIC KEY BLOCK-----";
+        let signing = signing(SigningMethod::Gpg, key);
+        let script = generate_install_script(&["tool"], "owner/repo", "1.0.0", &[], None, Some(&signing));
+        assert!(script.contains("curl -fsSL \"$URL.asc\" -o \"$TMPDIR/archive.asc\"\nmkdir -m 700 \"$TMPDIR/gnupg\"\n"));
+        assert!(script.contains(&format!("printf '%s\\n' '{key}' | gpg --homedir \"$TMPDIR/gnupg\" --batch --quiet --import\n")));
+        assert!(script.contains("--verify \"$TMPDIR/archive.asc\" \"$TMPDIR/archive\" 2>/dev/null || {"));
+    }
+
+    // --- generate_install_ps1 tests ---
+
+    #[test]
+    fn generate_install_ps1_maps_architectures_to_targets() {
+        let ps1 = generate_install_ps1(
+            &["tool", "tool-daemon"],
+            "owner/repo",
+            "1.0.0",
+            &[
+                ("x86_64-pc-windows-msvc", ArchiveFormat::Zip),
+                ("x86_64-pc-windows-gnu", ArchiveFormat::Zip),
+                ("aarch64-pc-windows-msvc", ArchiveFormat::TarGz),
+            ],
+            None,
+        );
+        assert!(ps1.contains("$Binary = 'tool'"));
+        assert!(ps1.contains("$Binaries = @('tool', 'tool-daemon')"));
+        assert!(ps1.contains("    'x86_64' { $Target = 'x86_64-pc-windows-msvc'; $Format = 'zip' }"));
+        assert!(ps1.contains("    'aarch64' { $Target = 'aarch64-pc-windows-msvc'; $Format = 'tar.gz' }"));
+        assert!(!ps1.contains("windows-gnu"));
+        assert!(!ps1.contains("'i686' {"));
+        assert!(ps1.contains("$Unpacked = $Tmp\n"));
+    }
+
+    #[test]
+    fn generate_install_ps1_looks_inside_archive_prefix() {
+        let ps1 = generate_install_ps1(
+            &["tool"],
+            "owner/repo",
+            "1.0.0",
+            &[("x86_64-pc-windows-msvc", ArchiveFormat::Zip)],
+            Some("{binary}-{version}-{target}"),
+        );
+        assert!(ps1.contains("$Unpacked = Join-Path $Tmp \"${Binary}-${Version}-${Target}\"\n"));
+    }
+
+    // --- render_install_scripts tests ---
+
+    fn archive(target: &str, format: ArchiveFormat) -> crate::artifact::Artifact {
+        crate::artifact::Artifact {
+            target: target.to_string(),
+            path: PathBuf::from(format!("tool-1.0.0-{target}.{}", format.extension())),
+            format,
+            files: Vec::new(),
+            digests: Default::default(),
+        }
+    }
+
+    #[test]
+    fn render_install_scripts_covers_only_built_targets() {
+        let toml = "[project]\nname = \"tool\"\nrepo = \"owner/tool\"\n\n[build]\npre_built_dir = \"dist\"\ntargets = [\"x86_64-unknown-linux-gnu\", \"aarch64-apple-darwin\", \"x86_64-pc-windows-msvc\"]\n\n[channels.curl]\n";
+        let config = Config::parse(toml).unwrap();
+        // aarch64-apple-darwin and the Windows target failed to build
+        let archives = vec![archive("x86_64-unknown-linux-gnu", ArchiveFormat::TarGz)];
+        let ctx = Context {
+            config: &config,
+            version: "1.0.0",
+            archives: &archives,
+            packages: &[],
+            channels: &["github", "curl"],
+        };
+        let scripts = render_install_scripts(&ctx);
+        assert_eq!(scripts.len(), 1);
+        let (name, script) = &scripts[0];
+        assert_eq!(*name, "install.sh");
+        assert!(script.contains("  x86_64-unknown-linux-gnu) FORMAT=\"tar.gz\" ;;\n"));
+        assert!(!script.contains("aarch64-apple-darwin"));
+    }
+}
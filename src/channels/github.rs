@@ -0,0 +1,349 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::sign::{SIGNATURE_CONTENT_TYPE, plan_signature, sign_file, signature_path};
+use super::{Channel, Context, Journal, Plan, Requirement, curl, github_token_requirement, staging_dir};
+use crate::artifact::{Digests, digest_reader};
+use crate::config::{ChecksumFormat, Config, DigestAlgorithm, Signing};
+use crate::github;
+use crate::provenance::provenance_path;
+
+const PROVENANCE_CONTENT_TYPE: &str = "application/jsonl";
+
+/// Creates the GitHub release and uploads the archives every other channel links to.
+pub struct GitHub;
+
+impl Channel for GitHub {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.github.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![github_token_requirement()]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_github(ctx)
+    }
+
+    fn publish(&self, ctx: &Context, journal: &mut Journal) -> Result<()> {
+        release_github(ctx, journal)
+    }
+}
+
+fn release_request_body(version: &str) -> serde_json::Value {
+    serde_json::json!({
+        "tag_name": format!("v{version}"),
+        "name": format!("v{version}"),
+        "generate_release_notes": true,
+    })
+}
+
+fn create_github_release(repo: &str, version: &str) -> Result<String> {
+    let url = format!("https://api.github.com/repos/{repo}/releases");
+    let body = release_request_body(version);
+    let resp = github::api("github", "POST", &url, Some(&body))?;
+    let upload_url = resp["upload_url"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("[github] missing upload_url in response"))?;
+    // Strip the {?name,label} URI template suffix
+    Ok(upload_url.split('{').next().unwrap_or(upload_url).to_string())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+}
+
+fn format_checksums(format: ChecksumFormat, algorithm: DigestAlgorithm, entries: &[(String, String)]) -> String {
+    match format {
+        ChecksumFormat::Gnu => entries.iter().map(|(name, hash)| format!("{hash}  {name}\n")).collect(),
+        ChecksumFormat::Bsd => entries
+            .iter()
+            .map(|(name, hash)| format!("{} ({name}) = {hash}\n", algorithm.label()))
+            .collect(),
+        ChecksumFormat::Json => {
+            let map: BTreeMap<&str, &str> = entries.iter().map(|(n, h)| (n.as_str(), h.as_str())).collect();
+            let mut json = serde_json::to_string_pretty(&map).expect("string map serializes");
+            json.push('\n');
+            json
+        }
+    }
+}
+
+fn checksum_content_type(format: ChecksumFormat) -> &'static str {
+    match format {
+        ChecksumFormat::Json => "application/json",
+        ChecksumFormat::Gnu | ChecksumFormat::Bsd => "text/plain",
+    }
+}
+
+/// A checksum manifest per digest algorithm covering every archive and package (and the
+/// install scripts when the curl channel is part of the release), plus optional per-archive
+/// checksum files. Returns `(file name, contents)` of the manifests and of the per-archive files.
+type Rendered = Vec<(String, String)>;
+
+fn render_checksums(ctx: &Context) -> Result<(Rendered, Rendered)> {
+    let ch = ctx.config.channels.github.as_ref().unwrap();
+    if !ch.checksums {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let extra = &ctx.config.build.digests;
+
+    let mut covered: Vec<(String, Digests)> = ctx.archives.iter().map(|a| (a.name(), a.digests.clone())).collect();
+    covered.extend(ctx.packages.iter().map(|p| (p.name(), p.digests.clone())));
+    if ctx.releases_to("curl") {
+        for (name, script) in curl::render_install_scripts(ctx) {
+            covered.push((name.to_string(), digest_reader(script.as_bytes(), extra)?));
+        }
+    }
+    covered.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut algorithms = vec![DigestAlgorithm::Sha256];
+    algorithms.extend(extra.iter().filter(|a| **a != DigestAlgorithm::Sha256));
+
+    let (mut manifests, mut per_archive) = (Vec::new(), Vec::new());
+    for algorithm in algorithms {
+        let entries: Vec<(String, String)> = covered
+            .iter()
+            .filter_map(|(name, digests)| digests.get(algorithm).map(|hash| (name.clone(), hash.to_string())))
+            .collect();
+        let manifest_name = match algorithm {
+            DigestAlgorithm::Sha256 => ch.checksum_file.as_str(),
+            other => other.manifest_name(),
+        };
+        manifests.push((manifest_name.to_string(), format_checksums(ch.checksum_format, algorithm, &entries)));
+
+        if ch.per_archive_checksums {
+            // archives from the journal of a resumed release lack digests added to the config since
+            let names: Vec<String> = ctx.archives.iter().map(|a| a.name()).chain(ctx.packages.iter().map(|p| p.name())).collect();
+            for entry in entries.iter().filter(|(name, _)| names.contains(name)) {
+                let name = format!("{}.{}", entry.0, algorithm.extension());
+                per_archive.push((name, format_checksums(ch.checksum_format, algorithm, std::slice::from_ref(entry))));
+            }
+        }
+    }
+    Ok((manifests, per_archive))
+}
+
+/// Writes the [`render_checksums`] files into the version's staging directory and returns
+/// the paths of the manifests and of the per-archive files.
+fn write_checksums(ctx: &Context) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let (manifests, per_archive) = render_checksums(ctx)?;
+    let dir = staging_dir(ctx.version);
+    std::fs::create_dir_all(&dir)?;
+    let write = |files: Vec<(String, String)>| -> Result<Vec<PathBuf>> {
+        files
+            .into_iter()
+            .map(|(name, content)| {
+                let path = dir.join(name);
+                std::fs::write(&path, content)?;
+                Ok(path)
+            })
+            .collect()
+    };
+    Ok((write(manifests)?, write(per_archive)?))
+}
+
+fn upload_once(upload_url: &str, path: &Path, content_type: &str, journal: &mut Journal) -> Result<()> {
+    let name = file_name(path);
+    let step = format!("github/{name}");
+    if journal.is_done(&step) {
+        println!("[github] {name} already uploaded, skipping");
+        return Ok(());
+    }
+    github::upload_asset("github", upload_url, path, &name, content_type)?;
+    journal.complete(&step)
+}
+
+/// Signs `path` and uploads the signature, unless an earlier attempt already uploaded it.
+fn upload_signature_once(upload_url: &str, signing: &Signing, path: &Path, journal: &mut Journal) -> Result<()> {
+    let signature = signature_path(signing, path);
+    if !journal.is_done(&format!("github/{}", file_name(&signature))) {
+        sign_file(signing, path)?;
+    }
+    upload_once(upload_url, &signature, SIGNATURE_CONTENT_TYPE, journal)
+}
+
+fn release_github(ctx: &Context, journal: &mut Journal) -> Result<()> {
+    let config = ctx.config;
+    let version = ctx.version;
+    let upload_url = match journal.upload_url.clone() {
+        Some(url) => {
+            println!("[github] Release v{version} already created, continuing uploads");
+            url
+        }
+        None => {
+            let url = create_github_release(&config.project.repo, version)?;
+            journal.upload_url = Some(url.clone());
+            journal.save()?;
+            url
+        }
+    };
+    let signing = config.signing.as_ref();
+    for archive in ctx.archives {
+        let mut assets = vec![(archive.path.clone(), archive.format.content_type())];
+        if config.build.provenance {
+            assets.push((provenance_path(&archive.path), PROVENANCE_CONTENT_TYPE));
+        }
+        for (path, content_type) in assets {
+            upload_once(&upload_url, &path, content_type, journal)?;
+            if let Some(signing) = signing {
+                upload_signature_once(&upload_url, signing, &path, journal)?;
+            }
+        }
+    }
+    for package in ctx.packages {
+        upload_once(&upload_url, &package.path, package.format.content_type(), journal)?;
+    }
+    let format = config.channels.github.as_ref().unwrap().checksum_format;
+    let (manifests, per_archive) = write_checksums(ctx)?;
+    for path in &manifests {
+        upload_once(&upload_url, path, checksum_content_type(format), journal)?;
+        if let Some(signing) = signing {
+            upload_signature_once(&upload_url, signing, path, journal)?;
+        }
+    }
+    for path in &per_archive {
+        upload_once(&upload_url, path, checksum_content_type(format), journal)?;
+    }
+    println!("[github] Created release v{version}");
+    Ok(())
+}
+
+fn plan_github(ctx: &Context) -> Result<Plan> {
+    let repo = &ctx.config.project.repo;
+    let mut plan = Plan::new("github");
+    plan.request(
+        "POST",
+        format!("https://api.github.com/repos/{repo}/releases"),
+        Some(release_request_body(ctx.version)),
+    );
+    let signing = ctx.config.signing.as_ref();
+    for archive in ctx.archives {
+        let mut assets = vec![(archive.path.clone(), archive.format.content_type())];
+        if ctx.config.build.provenance {
+            let statement = std::fs::read_to_string(provenance_path(&archive.path))?;
+            let rendered = plan.file(&file_name(&provenance_path(&archive.path)), statement);
+            assets.push((rendered, PROVENANCE_CONTENT_TYPE));
+        }
+        for (path, content_type) in assets {
+            plan.upload(repo, path.clone(), content_type);
+            if let Some(signing) = signing {
+                let signature = plan_signature(&mut plan, signing, &path);
+                plan.upload(repo, signature, SIGNATURE_CONTENT_TYPE);
+            }
+        }
+    }
+    for package in ctx.packages {
+        plan.upload(repo, package.path.clone(), package.format.content_type());
+    }
+    let format = ctx.config.channels.github.as_ref().unwrap().checksum_format;
+    let (manifests, per_archive) = render_checksums(ctx)?;
+    for (name, content) in manifests {
+        let rendered = plan.file(&name, content);
+        plan.upload(repo, rendered.clone(), checksum_content_type(format));
+        if let Some(signing) = signing {
+            let signature = plan_signature(&mut plan, signing, &rendered);
+            plan.upload(repo, signature, SIGNATURE_CONTENT_TYPE);
+        }
+    }
+    for (name, content) in per_archive {
+        let rendered = plan.file(&name, content);
+        plan.upload(repo, rendered, checksum_content_type(format));
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<(String, String)> {
+        vec![
+            ("tool-1.0.0-aarch64-apple-darwin.tar.gz".to_string(), "aaa".to_string()),
+            ("install.sh".to_string(), "bbb".to_string()),
+        ]
+    }
+
+    #[test]
+    fn format_checksums_gnu() {
+        assert_eq!(
+            format_checksums(ChecksumFormat::Gnu, DigestAlgorithm::Sha256, &entries()),
+            "aaa  tool-1.0.0-aarch64-apple-darwin.tar.gz\nbbb  install.sh\n"
+        );
+    }
+
+    #[test]
+    fn format_checksums_bsd() {
+        assert_eq!(
+            format_checksums(ChecksumFormat::Bsd, DigestAlgorithm::Sha256, &entries()),
+            "SHA256 (tool-1.0.0-aarch64-apple-darwin.tar.gz) = aaa\nSHA256 (install.sh) = bbb\n"
+        );
+        assert_eq!(
+            format_checksums(ChecksumFormat::Bsd, DigestAlgorithm::Blake3, &entries()[1..]),
+            "BLAKE3 (install.sh) = bbb\n"
+        );
+    }
+
+    #[test]
+    fn format_checksums_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&format_checksums(ChecksumFormat::Json, DigestAlgorithm::Sha512, &entries())).unwrap();
+        assert_eq!(json["install.sh"], "bbb");
+        assert_eq!(json["tool-1.0.0-aarch64-apple-darwin.tar.gz"], "aaa");
+    }
+
+    #[test]
+    fn upload_signature_once_skips_signing_on_resume() {
+        let signing = Signing {
+            method: crate::config::SigningMethod::Minisign,
+            key: "/nonexistent/release.key".to_string(),
+            public_key: None,
+            namespace: "file".to_string(),
+        };
+        let mut journal = Journal {
+            completed: vec!["github/tool.tar.gz.minisig".to_string()],
+            ..Default::default()
+        };
+        // signing with a missing key (or without minisign) would fail, as would uploading
+        upload_signature_once("https://uploads.invalid", &signing, Path::new("tool.tar.gz"), &mut journal).unwrap();
+    }
+
+    #[test]
+    fn write_checksums_skips_archives_missing_a_new_digest() {
+        let toml = "[project]\nname = \"tool\"\nrepo = \"owner/tool\"\n\n[build]\npre_built_dir = \"dist\"\ntargets = [\"x86_64-unknown-linux-gnu\"]\ndigests = [\"sha512\"]\n\n[channels.github]\nper_archive_checksums = true\n";
+        let config = Config::parse(toml).unwrap();
+        // hashed by an earlier attempt, before sha512 was configured
+        let archives = vec![crate::artifact::Artifact {
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            path: PathBuf::from("tool-0.0.0-resume-x86_64-unknown-linux-gnu.tar.gz"),
+            format: Default::default(),
+            files: Vec::new(),
+            digests: Digests {
+                sha256: "aaa".to_string(),
+                ..Default::default()
+            },
+        }];
+        let ctx = Context {
+            config: &config,
+            version: "0.0.0-resume",
+            archives: &archives,
+            packages: &[],
+            channels: &["github"],
+        };
+        let (manifests, per_archive) = render_checksums(&ctx).unwrap();
+        assert_eq!(manifests.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["SHA256SUMS", "SHA512SUMS"]);
+        assert_eq!(
+            per_archive,
+            vec![(
+                "tool-0.0.0-resume-x86_64-unknown-linux-gnu.tar.gz.sha256".to_string(),
+                "aaa  tool-0.0.0-resume-x86_64-unknown-linux-gnu.tar.gz\n".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,661 @@
+use anyhow::{Context as _, Result, bail};
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, archive_prefix, github_token_requirement, plan_repo_files,
+    to_pascal_case, update_repo_files,
+};
+use crate::artifact::Artifact;
+use crate::config::{Config, HomebrewKind};
+use crate::extras::{Shell, completions, man_pages};
+use crate::github;
+
+/// Pushes a formula or cask to a Homebrew tap.
+pub struct Homebrew;
+
+impl Channel for Homebrew {
+    fn name(&self) -> &str {
+        "homebrew"
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["github"]
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.homebrew.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![github_token_requirement()]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_homebrew(ctx.config, ctx.version, ctx.archives)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_homebrew(ctx.config, ctx.version, ctx.archives)
+    }
+}
+
+fn release_homebrew(
+    config: &Config,
+    version: &str,
+    archives: &[Artifact],
+) -> Result<()> {
+    let ch = config.channels.homebrew.as_ref().unwrap();
+    let formula_name = ch.formula_name.as_deref().unwrap_or(&config.project.name);
+    let repo = &config.project.repo;
+
+    let release_url = format!("https://api.github.com/repos/{repo}/releases/tags/v{version}");
+    github::api("homebrew", "GET", &release_url, None)
+        .with_context(|| format!("[homebrew] GitHub release v{version} not found — run the github channel first"))?;
+
+    let (file_path, content) = render_tap_file(config, version, archives)?;
+    let files = [(file_path.as_str(), Some(content.as_str()), format!("Update {formula_name} to {version}"))];
+    update_repo_files("homebrew", config, &ch.tap, &ch.update, formula_name, version, &files)?;
+    let kind = match ch.kind {
+        HomebrewKind::Formula => "formula",
+        HomebrewKind::Cask => "cask",
+    };
+    println!("[homebrew] Updated {kind} {formula_name} in {}", ch.tap);
+    Ok(())
+}
+
+/// Path in the tap and contents of the formula or cask.
+fn render_tap_file(config: &Config, version: &str, archives: &[Artifact]) -> Result<(String, String)> {
+    let ch = config.channels.homebrew.as_ref().unwrap();
+    let formula_name = ch.formula_name.as_deref().unwrap_or(&config.project.name);
+    Ok(match ch.kind {
+        HomebrewKind::Formula => (format!("Formula/{formula_name}.rb"), render_formula(config, version, archives)?),
+        HomebrewKind::Cask => (format!("Casks/{formula_name}.rb"), render_cask(config, version, archives)?),
+    })
+}
+
+/// One archive in the formula, installed from inside its `on_<os>` and `on_<cpu>` blocks.
+struct Platform {
+    os: &'static str,
+    cpu: &'static str,
+    asset: String,
+    sha256: String,
+}
+
+/// Homebrew's `(os, cpu)` block names for a target it can install.
+fn homebrew_platform(target: &str) -> Option<(&'static str, &'static str)> {
+    let os = if target.contains("apple-darwin") {
+        "macos"
+    } else if target.contains("-linux-") {
+        "linux"
+    } else {
+        return None;
+    };
+    let cpu = if target.starts_with("aarch64") {
+        "arm"
+    } else if target.starts_with("x86_64") {
+        "intel"
+    } else {
+        return None;
+    };
+    Some((os, cpu))
+}
+
+/// `package.description` and `package.license` from a Cargo.toml, when they are plain strings.
+fn cargo_package_metadata(manifest: &str) -> (Option<String>, Option<String>) {
+    let Ok(table) = manifest.parse::<toml::Table>() else { return (None, None) };
+    let field = |key| table.get("package")?.get(key)?.as_str().map(str::to_string);
+    (field("description"), field("license"))
+}
+
+fn render_formula(config: &Config, version: &str, archives: &[Artifact]) -> Result<String> {
+    let ch = config.channels.homebrew.as_ref().unwrap();
+    let formula_name = ch.formula_name.as_deref().unwrap_or(&config.project.name);
+
+    // only archives that were built; the first archive per platform wins, in the configured target order
+    let mut platforms: Vec<Platform> = Vec::new();
+    for archive in archives {
+        let Some((os, cpu)) = homebrew_platform(&archive.target) else { continue };
+        if platforms.iter().any(|p| p.os == os && p.cpu == cpu) {
+            continue;
+        }
+        platforms.push(Platform {
+            os,
+            cpu,
+            asset: archive.name(),
+            sha256: archive.sha256().to_string(),
+        });
+    }
+    if platforms.is_empty() {
+        bail!("[homebrew] no macOS or Linux archives to put in the formula");
+    }
+    // every archive carries the same extra files
+    let files = archives
+        .iter()
+        .find(|a| homebrew_platform(&a.target).is_some())
+        .map(|a| a.files.as_slice())
+        .unwrap_or_default();
+
+    let (cargo_description, cargo_license) = cargo_package_metadata(&std::fs::read_to_string("Cargo.toml").unwrap_or_default());
+    let binary = config.project.binary();
+    let formula = Formula {
+        name: formula_name,
+        desc: ch.description.clone().or(cargo_description).unwrap_or_else(|| formula_name.to_string()),
+        license: ch.license.clone().or(cargo_license),
+        repo: &config.project.repo,
+        version,
+        binaries: config.project.binaries(),
+        depends_on: &ch.depends_on,
+        caveats: ch.caveats.as_deref(),
+        test: ch.test.clone().unwrap_or_else(|| format!("#{{bin}}/{binary} --version")),
+        generate_completions: ch.generate_completions.as_deref(),
+    };
+    Ok(generate_formula(&formula, &platforms, files))
+}
+
+/// `bin.install` plus completion and man page installs for the archive's extra files. Completions
+/// generated by the first binary replace the bundled ones.
+fn install_lines(binaries: &[&str], files: &[String], generate_completions: Option<&[String]>) -> String {
+    let quoted: Vec<String> = binaries.iter().map(|b| format!("\"{b}\"")).collect();
+    let mut lines = vec![format!("    bin.install {}", quoted.join(", "))];
+    match generate_completions {
+        Some(args) => {
+            let mut call = format!("    generate_completions_from_executable(bin/\"{}\"", binaries[0]);
+            for arg in args {
+                call.push_str(&format!(", \"{}\"", ruby_escape(arg)));
+            }
+            call.push(')');
+            lines.push(call);
+        }
+        None => {
+            for (shell, path, command) in completions(files) {
+                let line = match shell {
+                    Shell::Zsh if path.ends_with(".zsh") => {
+                        format!("    zsh_completion.install \"{path}\" => \"_{command}\"")
+                    }
+                    _ => format!("    {}_completion.install \"{path}\"", shell.name()),
+                };
+                lines.push(line);
+            }
+        }
+    }
+    for (section, path) in man_pages(files) {
+        lines.push(format!("    man{section}.install \"{path}\""));
+    }
+    lines.join("\n")
+}
+
+/// Escapes a double-quoted Ruby string, leaving `#{...}` interpolation intact.
+fn ruby_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Homebrew's form of an SPDX expression: `A OR B` (or Cargo's old `A/B`) becomes `any_of:` and
+/// `A AND B` becomes `all_of:`. Anything more nested stays a plain string.
+fn license_expression(spdx: &str) -> String {
+    let quoted = |ids: Vec<&str>| ids.iter().map(|id| format!("\"{}\"", ruby_escape(id.trim()))).collect::<Vec<_>>().join(", ");
+    if !spdx.contains('(') {
+        let any: Vec<&str> = spdx.split(" OR ").flat_map(|id| id.split('/')).collect();
+        let all: Vec<&str> = spdx.split(" AND ").collect();
+        if any.len() > 1 && !spdx.contains(" AND ") {
+            return format!("any_of: [{}]", quoted(any));
+        }
+        if all.len() > 1 && !spdx.contains(" OR ") {
+            return format!("all_of: [{}]", quoted(all));
+        }
+    }
+    format!("\"{}\"", ruby_escape(spdx))
+}
+
+/// Everything in the formula besides the archives.
+struct Formula<'a> {
+    name: &'a str,
+    desc: String,
+    license: Option<String>,
+    repo: &'a str,
+    version: &'a str,
+    binaries: Vec<&'a str>,
+    depends_on: &'a [String],
+    caveats: Option<&'a str>,
+    /// Shell command for `brew test`
+    test: String,
+    generate_completions: Option<&'a [String]>,
+}
+
+/// `on_macos` and `on_linux` blocks for the platforms that were built.
+fn platform_blocks(repo: &str, version: &str, platforms: &[Platform]) -> String {
+    let mut blocks = Vec::new();
+    for os in ["macos", "linux"] {
+        let mut block = String::new();
+        for cpu in ["arm", "intel"] {
+            let Some(p) = platforms.iter().find(|p| p.os == os && p.cpu == cpu) else { continue };
+            block.push_str(&format!(
+                "    on_{cpu} do\n      url \"https://github.com/{repo}/releases/download/v{version}/{}\"\n      sha256 \"{}\"\n    end\n",
+                p.asset, p.sha256
+            ));
+        }
+        if !block.is_empty() {
+            blocks.push(format!("  on_{os} do\n{block}  end\n"));
+        }
+    }
+    blocks.join("\n")
+}
+
+fn generate_formula(formula: &Formula, platforms: &[Platform], files: &[String]) -> String {
+    let (name, repo, version) = (formula.name, formula.repo, formula.version);
+    let mut out = format!(
+        "class {} < Formula\n  desc \"{}\"\n  homepage \"https://github.com/{repo}\"\n  version \"{version}\"\n",
+        to_pascal_case(name),
+        ruby_escape(&formula.desc),
+    );
+    if let Some(license) = &formula.license {
+        out.push_str(&format!("  license {}\n", license_expression(license)));
+    }
+    if !formula.depends_on.is_empty() {
+        out.push('\n');
+        for dependency in formula.depends_on {
+            out.push_str(&format!("  depends_on \"{}\"\n", ruby_escape(dependency)));
+        }
+    }
+    out.push('\n');
+    out.push_str(&platform_blocks(repo, version, platforms));
+    out.push_str(&format!(
+        "\n  def install\n{}\n  end\n",
+        install_lines(&formula.binaries, files, formula.generate_completions)
+    ));
+    if let Some(caveats) = formula.caveats {
+        out.push_str(&format!("\n  def caveats\n    <<~EOS\n{}    EOS\n  end\n", heredoc_body(caveats, "      ")));
+    }
+    out.push_str(&format!("\n  test do\n    system \"{}\"\n  end\nend\n", ruby_escape(&formula.test)));
+    out
+}
+
+/// Lines of a `<<~EOS` heredoc, indented by `indent` except where blank.
+fn heredoc_body(text: &str, indent: &str) -> String {
+    let mut out = String::new();
+    for line in text.trim_end().lines() {
+        if !line.is_empty() {
+            out.push_str(indent);
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Everything in the cask. Its `url` and artifact paths interpolate `#{version}`, and `#{arch}`
+/// when both architectures were built.
+struct Cask<'a> {
+    token: &'a str,
+    name: &'a str,
+    desc: String,
+    repo: &'a str,
+    version: &'a str,
+    /// `(cpu, sha256)` of each macOS archive, `arm` first
+    sha256: Vec<(&'static str, String)>,
+    asset: String,
+    /// Directory the archives unpack into, if any
+    prefix: Option<String>,
+    app: Option<&'a str>,
+    binaries: Vec<&'a str>,
+    depends_on: &'a [String],
+    caveats: Option<&'a str>,
+}
+
+/// `s` with `version`, and the CPU in `target` when there are two architectures, replaced by
+/// the cask's `#{version}` and `#{arch}` interpolations.
+fn cask_interpolate(s: &str, version: &str, target: Option<&str>) -> String {
+    let s = s.replace(version, "#{version}");
+    match target {
+        Some(target) => {
+            let rest = target.split_once('-').map_or("", |(_, rest)| rest);
+            s.replace(target, &format!("#{{arch}}-{rest}"))
+        }
+        None => s,
+    }
+}
+
+fn render_cask(config: &Config, version: &str, archives: &[Artifact]) -> Result<String> {
+    let ch = config.channels.homebrew.as_ref().unwrap();
+    let token = ch.formula_name.as_deref().unwrap_or(&config.project.name);
+
+    // the first macOS archive per architecture wins, like the formula
+    let mut mac: Vec<(&'static str, &Artifact)> = Vec::new();
+    for archive in archives {
+        if let Some(("macos", cpu)) = homebrew_platform(&archive.target)
+            && !mac.iter().any(|(c, _)| *c == cpu)
+        {
+            mac.push((cpu, archive));
+        }
+    }
+    if mac.is_empty() {
+        bail!("[homebrew] no macOS archives to put in the cask");
+    }
+    mac.sort_by_key(|(cpu, _)| *cpu != "arm");
+
+    let both = mac.len() == 2;
+    let mut asset_prefix: Option<(String, Option<String>)> = None;
+    for (_, archive) in &mac {
+        let arch_target = both.then_some(archive.target.as_str());
+        let asset = cask_interpolate(&archive.name(), version, arch_target);
+        let prefix = archive_prefix(config, version, &archive.target).map(|p| cask_interpolate(&p, version, arch_target));
+        match &asset_prefix {
+            Some(first) if *first != (asset.clone(), prefix.clone()) => bail!(
+                "[homebrew] the cask's arm and intel archives must be named alike apart from the architecture, got {} and {}",
+                mac[0].1.name(),
+                archive.name()
+            ),
+            Some(_) => {}
+            None => asset_prefix = Some((asset, prefix)),
+        }
+    }
+    let (asset, prefix) = asset_prefix.unwrap();
+
+    let cargo_description = cargo_package_metadata(&std::fs::read_to_string("Cargo.toml").unwrap_or_default()).0;
+    let cask = Cask {
+        token,
+        name: &config.project.name,
+        desc: ch.description.clone().or(cargo_description).unwrap_or_else(|| token.to_string()),
+        repo: &config.project.repo,
+        version,
+        sha256: mac.iter().map(|(cpu, a)| (*cpu, a.sha256().to_string())).collect(),
+        asset,
+        prefix,
+        app: ch.app.as_deref(),
+        binaries: config.project.binaries(),
+        depends_on: &ch.depends_on,
+        caveats: ch.caveats.as_deref(),
+    };
+    Ok(generate_cask(&cask))
+}
+
+fn generate_cask(cask: &Cask) -> String {
+    let repo = cask.repo;
+    let mut out = format!("cask \"{}\" do\n", ruby_escape(cask.token));
+    match cask.sha256.as_slice() {
+        [(_, sha)] => out.push_str(&format!("  version \"{}\"\n  sha256 \"{sha}\"\n", cask.version)),
+        shas => {
+            out.push_str("  arch arm: \"aarch64\", intel: \"x86_64\"\n\n");
+            out.push_str(&format!("  version \"{}\"\n", cask.version));
+            let pairs: Vec<String> = shas.iter().map(|(cpu, sha)| format!("{:<6} \"{sha}\"", format!("{cpu}:"))).collect();
+            out.push_str(&format!("  sha256 {}\n", pairs.join(",\n         ")));
+        }
+    }
+    out.push_str(&format!(
+        "\n  url \"https://github.com/{repo}/releases/download/v#{{version}}/{}\"\n  name \"{}\"\n  desc \"{}\"\n  homepage \"https://github.com/{repo}\"\n",
+        cask.asset,
+        ruby_escape(cask.name),
+        ruby_escape(&cask.desc),
+    ));
+    out.push_str("\n  livecheck do\n    url :url\n    strategy :github_latest\n  end\n");
+
+    let mut depends = Vec::new();
+    if let [(cpu, _)] = cask.sha256.as_slice() {
+        depends.push(format!("  depends_on arch: :{}\n", if *cpu == "arm" { "arm64" } else { "x86_64" }));
+    }
+    for formula in cask.depends_on {
+        depends.push(format!("  depends_on formula: \"{}\"\n", ruby_escape(formula)));
+    }
+    if !depends.is_empty() {
+        out.push('\n');
+        out.push_str(&depends.concat());
+    }
+
+    let path = |file: &str| match &cask.prefix {
+        Some(prefix) => format!("{prefix}/{file}"),
+        None => file.to_string(),
+    };
+    out.push('\n');
+    if let Some(app) = cask.app {
+        out.push_str(&format!("  app \"{}\"\n", path(app)));
+    }
+    for binary in &cask.binaries {
+        out.push_str(&format!("  binary \"{}\"\n", path(binary)));
+    }
+    if let Some(caveats) = cask.caveats {
+        out.push_str(&format!("\n  caveats <<~EOS\n{}  EOS\n", heredoc_body(caveats, "    ")));
+    }
+    out.push_str("end\n");
+    out
+}
+
+fn plan_homebrew(config: &Config, version: &str, archives: &[Artifact]) -> Result<Plan> {
+    let ch = config.channels.homebrew.as_ref().unwrap();
+    let formula_name = ch.formula_name.as_deref().unwrap_or(&config.project.name);
+    let (file_path, content) = render_tap_file(config, version, archives)?;
+
+    let mut plan = Plan::new("homebrew");
+    let files = [(file_path.as_str(), Some(content.as_str()), format!("Update {formula_name} to {version}"))];
+    plan_repo_files(&mut plan, config, &ch.tap, &ch.update, formula_name, version, &files);
+    plan.file(&file_path, content);
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(os: &'static str, cpu: &'static str, (asset, sha256): (&str, &str)) -> Platform {
+        Platform {
+            os,
+            cpu,
+            asset: asset.to_string(),
+            sha256: sha256.to_string(),
+        }
+    }
+
+    /// `arm` and `intel` are `(asset name, sha256)` pairs.
+    fn macos(arm: (&str, &str), intel: (&str, &str)) -> Vec<Platform> {
+        vec![platform("macos", "arm", arm), platform("macos", "intel", intel)]
+    }
+
+    fn formula<'a>(name: &'a str, binaries: &[&'a str], version: &'a str) -> Formula<'a> {
+        Formula {
+            name,
+            desc: name.to_string(),
+            license: None,
+            repo: "owner/repo",
+            version,
+            binaries: binaries.to_vec(),
+            depends_on: &[],
+            caveats: None,
+            test: format!("#{{bin}}/{} --version", binaries[0]),
+            generate_completions: None,
+        }
+    }
+
+    // --- generate_formula tests ---
+
+    #[test]
+    fn generate_formula_correct_class_name() {
+        let formula = generate_formula(&formula("my-tool", &["my-tool"], "1.0.0"), &macos(("a.tar.gz", "abc"), ("b.tar.gz", "def")), &[]);
+        assert!(formula.starts_with("class MyTool < Formula"));
+    }
+
+    #[test]
+    fn generate_formula_contains_version() {
+        let formula = generate_formula(&formula("tool", &["tool"], "2.3.4"), &macos(("a.tar.gz", "abc"), ("b.tar.gz", "def")), &[]);
+        assert!(formula.contains("version \"2.3.4\""));
+    }
+
+    #[test]
+    fn generate_formula_contains_arch_blocks() {
+        let formula = generate_formula(&formula("tool", &["tool"], "1.0.0"), &macos(("a.tar.gz", "armsha"), ("b.tar.gz", "intelsha")), &[]);
+        assert!(formula.contains("on_macos do"));
+        assert!(formula.contains("on_arm do"));
+        assert!(formula.contains("on_intel do"));
+        assert!(formula.contains("sha256 \"armsha\""));
+        assert!(formula.contains("sha256 \"intelsha\""));
+    }
+
+    #[test]
+    fn generate_formula_contains_download_urls() {
+        let formula = generate_formula(
+            &formula("tool", &["tool"], "1.0.0"),
+            &macos(("tool-1.0.0-aarch64-apple-darwin.tar.gz", "a"), ("tool-1.0.0-x86_64-apple-darwin.zip", "b")),
+            &[],
+        );
+        assert!(formula.contains("https://github.com/owner/repo/releases/download/v1.0.0/tool-1.0.0-aarch64-apple-darwin.tar.gz"));
+        assert!(formula.contains("https://github.com/owner/repo/releases/download/v1.0.0/tool-1.0.0-x86_64-apple-darwin.zip"));
+    }
+
+    #[test]
+    fn generate_formula_contains_binary_install() {
+        let formula = generate_formula(&formula("tool", &["mybinary"], "1.0.0"), &macos(("a.tar.gz", "a"), ("b.tar.gz", "b")), &[]);
+        assert!(formula.contains("bin.install \"mybinary\""));
+    }
+
+    #[test]
+    fn generate_formula_installs_every_binary() {
+        let formula = generate_formula(&formula("tool", &["tool", "tool-daemon"], "1.0.0"), &macos(("a.tar.gz", "a"), ("b.tar.gz", "b")), &[]);
+        assert!(formula.contains("    bin.install \"tool\", \"tool-daemon\"\n"));
+    }
+
+    #[test]
+    fn generate_formula_installs_completions_and_man_pages() {
+        let files: Vec<String> = ["LICENSE", "completions/tool.bash", "completions/tool.zsh", "completions/tool.fish", "man/tool.1"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        let formula = generate_formula(&formula("tool", &["tool"], "1.0.0"), &macos(("a.tar.gz", "a"), ("b.tar.gz", "b")), &files);
+        assert!(formula.contains(
+            "    bin.install \"tool\"\n    bash_completion.install \"completions/tool.bash\"\n    zsh_completion.install \"completions/tool.zsh\" => \"_tool\"\n    fish_completion.install \"completions/tool.fish\"\n    man1.install \"man/tool.1\"\n  end"
+        ));
+        assert!(!formula.contains("LICENSE"));
+    }
+
+    #[test]
+    fn generate_formula_adds_linux_blocks() {
+        let platforms = vec![
+            platform("linux", "intel", ("tool-x86_64-unknown-linux-gnu.tar.gz", "linuxintel")),
+            platform("macos", "arm", ("tool-aarch64-apple-darwin.tar.gz", "macarm")),
+            platform("linux", "arm", ("tool-aarch64-unknown-linux-gnu.tar.gz", "linuxarm")),
+        ];
+        let formula = generate_formula(&formula("tool", &["tool"], "1.0.0"), &platforms, &[]);
+        let url = "https://github.com/owner/repo/releases/download/v1.0.0";
+        assert!(formula.contains(&format!(
+            "  version \"1.0.0\"\n\n  on_macos do\n    on_arm do\n      url \"{url}/tool-aarch64-apple-darwin.tar.gz\"\n      sha256 \"macarm\"\n    end\n  end\n\n  on_linux do\n    on_arm do\n      url \"{url}/tool-aarch64-unknown-linux-gnu.tar.gz\"\n      sha256 \"linuxarm\"\n    end\n    on_intel do\n      url \"{url}/tool-x86_64-unknown-linux-gnu.tar.gz\"\n      sha256 \"linuxintel\"\n    end\n  end\n\n  def install\n"
+        )));
+    }
+
+    #[test]
+    fn generate_formula_skips_platforms_that_were_not_built() {
+        let platforms = vec![platform("linux", "intel", ("tool-x86_64-unknown-linux-musl.tar.gz", "linuxintel"))];
+        let formula = generate_formula(&formula("tool", &["tool"], "1.0.0"), &platforms, &[]);
+        assert!(!formula.contains("on_macos"));
+        assert!(!formula.contains("on_arm"));
+        assert!(!formula.contains("sha256 \"\""));
+        assert!(formula.contains("  on_linux do\n    on_intel do\n"));
+    }
+
+    #[test]
+    fn homebrew_platform_maps_targets() {
+        assert_eq!(homebrew_platform("aarch64-apple-darwin"), Some(("macos", "arm")));
+        assert_eq!(homebrew_platform("x86_64-unknown-linux-musl"), Some(("linux", "intel")));
+        assert_eq!(homebrew_platform("x86_64-pc-windows-msvc"), None);
+        assert_eq!(homebrew_platform("armv7-unknown-linux-gnueabihf"), None);
+    }
+
+    #[test]
+    fn generate_formula_adds_metadata_and_test_block() {
+        let depends_on = vec!["openssl@3".to_string()];
+        let mut formula = formula("tool", &["tool"], "1.0.0");
+        formula.desc = "A \"fast\" tool".to_string();
+        formula.license = Some("MIT OR Apache-2.0".to_string());
+        formula.depends_on = &depends_on;
+        formula.caveats = Some("Run `tool init` first.\n\nThen enjoy.\n");
+        let rendered = generate_formula(&formula, &macos(("a.tar.gz", "a"), ("b.tar.gz", "b")), &[]);
+        assert!(rendered.starts_with(
+            "class Tool < Formula\n  desc \"A \\\"fast\\\" tool\"\n  homepage \"https://github.com/owner/repo\"\n  version \"1.0.0\"\n  license any_of: [\"MIT\", \"Apache-2.0\"]\n\n  depends_on \"openssl@3\"\n\n  on_macos do\n"
+        ));
+        assert!(rendered.ends_with(
+            "  end\n\n  def caveats\n    <<~EOS\n      Run `tool init` first.\n\n      Then enjoy.\n    EOS\n  end\n\n  test do\n    system \"#{bin}/tool --version\"\n  end\nend\n"
+        ));
+    }
+
+    #[test]
+    fn generate_formula_generates_completions_instead_of_bundled_ones() {
+        let args = vec!["completions".to_string()];
+        let mut formula = formula("tool", &["tool", "toold"], "1.0.0");
+        formula.generate_completions = Some(&args);
+        let files = vec!["completions/tool.bash".to_string(), "man/tool.1".to_string()];
+        let rendered = generate_formula(&formula, &macos(("a.tar.gz", "a"), ("b.tar.gz", "b")), &files);
+        assert!(rendered.contains(
+            "    bin.install \"tool\", \"toold\"\n    generate_completions_from_executable(bin/\"tool\", \"completions\")\n    man1.install \"man/tool.1\"\n"
+        ));
+        assert!(!rendered.contains("bash_completion.install"));
+    }
+
+    // --- generate_cask tests ---
+
+    fn cask<'a>(sha256: Vec<(&'static str, String)>, asset: &str) -> Cask<'a> {
+        Cask {
+            token: "tool",
+            name: "Tool",
+            desc: "Does things".to_string(),
+            repo: "owner/repo",
+            version: "1.0.0",
+            sha256,
+            asset: asset.to_string(),
+            prefix: None,
+            app: Some("Tool.app"),
+            binaries: vec!["tool"],
+            depends_on: &[],
+            caveats: None,
+        }
+    }
+
+    #[test]
+    fn generate_cask_pairs_arch_sha256() {
+        let shas = vec![("arm", "armsha".to_string()), ("intel", "intelsha".to_string())];
+        let rendered = generate_cask(&cask(shas, "tool-#{version}-#{arch}-apple-darwin.zip"));
+        assert_eq!(
+            rendered,
+            "cask \"tool\" do\n  arch arm: \"aarch64\", intel: \"x86_64\"\n\n  version \"1.0.0\"\n  sha256 arm:   \"armsha\",\n         intel: \"intelsha\"\n\n  url \"https://github.com/owner/repo/releases/download/v#{version}/tool-#{version}-#{arch}-apple-darwin.zip\"\n  name \"Tool\"\n  desc \"Does things\"\n  homepage \"https://github.com/owner/repo\"\n\n  livecheck do\n    url :url\n    strategy :github_latest\n  end\n\n  app \"Tool.app\"\n  binary \"tool\"\nend\n"
+        );
+    }
+
+    #[test]
+    fn generate_cask_with_one_arch_depends_on_it() {
+        let depends_on = vec!["ffmpeg".to_string()];
+        let mut cask = cask(vec![("arm", "armsha".to_string())], "tool-#{version}-aarch64-apple-darwin.zip");
+        cask.prefix = Some("tool-#{version}".to_string());
+        cask.depends_on = &depends_on;
+        cask.caveats = Some("Open Tool once.\n");
+        let rendered = generate_cask(&cask);
+        assert!(rendered.starts_with("cask \"tool\" do\n  version \"1.0.0\"\n  sha256 \"armsha\"\n\n"));
+        assert!(!rendered.contains("arch arm:"));
+        assert!(rendered.ends_with(
+            "  end\n\n  depends_on arch: :arm64\n  depends_on formula: \"ffmpeg\"\n\n  app \"tool-#{version}/Tool.app\"\n  binary \"tool-#{version}/tool\"\n\n  caveats <<~EOS\n    Open Tool once.\n  EOS\nend\n"
+        ));
+    }
+
+    #[test]
+    fn cask_interpolate_replaces_version_and_arch() {
+        assert_eq!(
+            cask_interpolate("tool-1.0.0-aarch64-apple-darwin.zip", "1.0.0", Some("aarch64-apple-darwin")),
+            "tool-#{version}-#{arch}-apple-darwin.zip"
+        );
+        assert_eq!(
+            cask_interpolate("tool-1.0.0-x86_64-apple-darwin.zip", "1.0.0", Some("x86_64-apple-darwin")),
+            "tool-#{version}-#{arch}-apple-darwin.zip"
+        );
+        assert_eq!(cask_interpolate("tool-1.0.0-x86_64-apple-darwin.zip", "1.0.0", None), "tool-#{version}-x86_64-apple-darwin.zip");
+    }
+
+    #[test]
+    fn cargo_package_metadata_reads_description_and_license() {
+        let manifest = "[package]\nname = \"tool\"\ndescription = \"Does things\"\nlicense = \"MIT\"\n";
+        assert_eq!(cargo_package_metadata(manifest), (Some("Does things".to_string()), Some("MIT".to_string())));
+        let workspace = "[package]\nname = \"tool\"\nlicense.workspace = true\n";
+        assert_eq!(cargo_package_metadata(workspace), (None, None));
+        assert_eq!(cargo_package_metadata(""), (None, None));
+    }
+
+    #[test]
+    fn license_expression_uses_homebrew_combinators() {
+        assert_eq!(license_expression("MIT"), "\"MIT\"");
+        assert_eq!(license_expression("MIT OR Apache-2.0"), "any_of: [\"MIT\", \"Apache-2.0\"]");
+        assert_eq!(license_expression("MIT/Apache-2.0"), "any_of: [\"MIT\", \"Apache-2.0\"]");
+        assert_eq!(license_expression("MIT AND BSD-3-Clause"), "all_of: [\"MIT\", \"BSD-3-Clause\"]");
+        assert_eq!(license_expression("(MIT OR Apache-2.0) AND Zlib"), "\"(MIT OR Apache-2.0) AND Zlib\"");
+    }
+}
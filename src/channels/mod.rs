@@ -0,0 +1,1649 @@
+use anyhow::{Context as _, Result, bail};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::archive;
+use crate::artifact::Artifact;
+use crate::config::{Config, HostedRepo, MergeMethod, RepoUpdate, UpdateMode};
+use crate::package::{self, Package};
+use crate::provenance;
+
+mod apt_repo;
+mod aur;
+mod cargo;
+mod curl;
+mod github;
+mod homebrew;
+mod nix;
+mod plugin;
+mod scoop;
+mod sign;
+mod winget;
+mod yum_repo;
+
+// --- Shared infrastructure ---
+
+fn run_cmd(label: &str, dir: Option<&Path>, cmd: &str, args: &[&str]) -> Result<String> {
+    println!("[{label}] Running: {cmd} {}", args.join(" "));
+    let mut command = Command::new(cmd);
+    command.args(args);
+    if let Some(d) = dir {
+        command.current_dir(d);
+    }
+    let output = command
+        .output()
+        .with_context(|| format!("[{label}] failed to run {cmd}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("[{label}] {cmd} failed: {stderr}");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn substitute(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut result = template.to_string();
+    for (key, value) in vars {
+        result = result.replace(&format!("{{{key}}}"), value);
+    }
+    result
+}
+
+fn detect_version(config: &Config, version_override: Option<&str>) -> Result<String> {
+    if let Some(v) = version_override {
+        return Ok(v.strip_prefix('v').unwrap_or(v).to_string());
+    }
+    let raw = if let Some(cmd) = &config.project.version_command {
+        let parts: Vec<&str> = cmd.split_whitespace().collect();
+        let (bin, args) = parts
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("empty version_command"))?;
+        run_cmd("version", None, bin, args)
+            .context("version_command failed")?
+    } else {
+        run_cmd("version", None, "git", &["describe", "--tags", "--abbrev=0"])
+            .context("could not detect version from git tags — use --version or set version_command in config")?
+    };
+    Ok(raw.strip_prefix('v').unwrap_or(&raw).to_string())
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{prompt} [y/N] ");
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+fn parse_host_target(rustc_output: &str) -> Option<String> {
+    rustc_output
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|s| s.trim().to_string())
+}
+
+/// Output of `rustc -vV`, which names the host target and the compiler version.
+fn rustc_version_verbose() -> Option<String> {
+    Command::new("rustc")
+        .args(["-vV"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+pub(crate) fn is_windows(target: &str) -> bool {
+    target.contains("-windows-")
+}
+
+/// Executable suffix for binaries built for `target`, the value of the `{exe}` placeholder.
+fn exe_suffix(target: &str) -> &'static str {
+    if is_windows(target) { ".exe" } else { "" }
+}
+
+fn needs_cross_linker(host: &str, target: &str) -> bool {
+    if host == target {
+        return false;
+    }
+    // macOS toolchain handles both x86_64 and aarch64 natively
+    if host.contains("darwin") && target.contains("darwin") {
+        return false;
+    }
+    true
+}
+
+fn has_cargo_zigbuild() -> bool {
+    Command::new("cargo-zigbuild")
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+fn parse_installed_targets(output: &str) -> HashSet<String> {
+    output.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect()
+}
+
+fn installed_targets() -> HashSet<String> {
+    Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_installed_targets(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// Directory holding every file in `target`'s archive, when `[build] archive_prefix` is set.
+fn archive_prefix(config: &Config, version: &str, target: &str) -> Option<String> {
+    let template = config.build.archive_prefix.as_deref()?;
+    let vars = &[("target", target), ("binary", config.project.binary()), ("version", version)];
+    let prefix = substitute(template, vars);
+    let prefix = prefix.trim_matches('/');
+    (!prefix.is_empty()).then(|| prefix.to_string())
+}
+
+/// Builds every target and packs its archive, plus any `[packages]` for it.
+fn build_artifacts(config: &Config, version: &str) -> Result<(Vec<Artifact>, Vec<Package>)> {
+    let binary = config.project.binary();
+    let binaries = config.project.binaries();
+    let staging = PathBuf::from("target/release-staging");
+    std::fs::create_dir_all(&staging)?;
+
+    let rustc = rustc_version_verbose();
+    let host = rustc.as_deref().and_then(parse_host_target).unwrap_or_default();
+    let zigbuild_available = has_cargo_zigbuild();
+    let build_info = provenance::BuildInfo {
+        repo: &config.project.repo,
+        version,
+        git_commit: provenance::git_commit(),
+        rustc: rustc.as_deref(),
+        pre_built_dir: config.build.pre_built_dir.as_deref(),
+    };
+    let mtime = archive::source_date_epoch();
+    let includes: Vec<archive::Entry> = archive::include_entries(&config.build.include)?
+        .into_iter()
+        .filter(|e| !binaries.contains(&e.name.as_str()))
+        .collect();
+
+    let mut archives = Vec::new();
+    let mut packages = Vec::new();
+    let mut failed = Vec::new();
+    for target in &config.build.targets {
+        let mut commands = Vec::new();
+        if let Some(cmd_template) = &config.build.command {
+            // a command mentioning {binary} builds one binary at a time
+            let per_binary = if cmd_template.contains("{binary}") { binaries.clone() } else { vec![binary] };
+            let mut build_failed = false;
+            for bin_name in per_binary {
+                let vars = &[
+                    ("target", target.as_str()),
+                    ("binary", bin_name),
+                    ("version", version),
+                    ("exe", exe_suffix(target)),
+                ];
+                let cmd_str = substitute(cmd_template, vars);
+                let cmd_str = if cmd_str.contains("cargo build")
+                    && zigbuild_available
+                    && needs_cross_linker(&host, target)
+                {
+                    eprintln!("[build] Using cargo-zigbuild for cross-compilation target {target}");
+                    cmd_str.replace("cargo build", "cargo zigbuild")
+                } else {
+                    cmd_str
+                };
+                let parts: Vec<&str> = cmd_str.split_whitespace().collect();
+                let (bin, args) = parts
+                    .split_first()
+                    .ok_or_else(|| anyhow::anyhow!("empty build command"))?;
+                if let Err(e) = run_cmd("build", None, bin, args) {
+                    eprintln!("[build] Warning: target {target} failed: {e}");
+                    build_failed = true;
+                    break;
+                }
+                commands.push(cmd_str);
+            }
+            if build_failed {
+                failed.push(target.clone());
+                continue;
+            }
+        }
+
+        let mut entries = Vec::new();
+        for bin_name in &binaries {
+            let exe = exe_suffix(target);
+            let vars = &[
+                ("target", target.as_str()),
+                ("binary", *bin_name),
+                ("version", version),
+                ("exe", exe),
+            ];
+            let artifact_path = if config.build.command.is_some() {
+                let artifact_template = config
+                    .build
+                    .artifact_for(bin_name)
+                    .expect("artifact required with command");
+                PathBuf::from(substitute(artifact_template, vars))
+            } else {
+                let dir = config
+                    .build
+                    .pre_built_dir
+                    .as_ref()
+                    .expect("pre_built_dir required");
+                PathBuf::from(substitute(dir, vars)).join(format!("{bin_name}-{target}"))
+            };
+            // Windows builds produce `<name>.exe` even when the template leaves it out
+            let artifact_path = match artifact_path.with_added_extension("exe") {
+                with_exe if !exe.is_empty() && !artifact_path.exists() && with_exe.exists() => with_exe,
+                _ => artifact_path,
+            };
+            if !artifact_path.exists() {
+                eprintln!("[build] Warning: target {target} failed: artifact not found at {}", artifact_path.display());
+                break;
+            }
+            entries.push(archive::Entry {
+                name: format!("{bin_name}{exe}"),
+                source: artifact_path,
+                executable: true,
+            });
+        }
+        if entries.len() < binaries.len() {
+            failed.push(target.clone());
+            continue;
+        }
+
+        packages.extend(package::build_packages(config, version, target, &entries, &includes, &staging, mtime)?);
+
+        let format = config.build.archive_format_for(target);
+        let archive_path = staging.join(archive::archive_name(binary, version, target, format));
+        entries.extend(includes.iter().cloned());
+        if let Some(prefix) = archive_prefix(config, version, target) {
+            for entry in &mut entries {
+                entry.name = format!("{prefix}/{}", entry.name);
+            }
+        }
+        println!("[build] Packing {}", archive_path.display());
+        archive::write_archive(&archive_path, format, &entries, mtime)?;
+
+        let files = includes.iter().map(|e| e.name.clone()).collect();
+        let artifact = Artifact::new(target, archive_path, format, files, &config.build.digests)?;
+        if config.build.provenance {
+            provenance::write_provenance(&artifact, &commands, &build_info)?;
+        }
+        archives.push(artifact);
+    }
+
+    if archives.is_empty() {
+        bail!("all build targets failed");
+    }
+
+    if !failed.is_empty() {
+        eprintln!("\n{}/{} targets failed:", failed.len(), config.build.targets.len());
+        for t in &failed {
+            eprintln!("  - {t}");
+        }
+        if config.build.command.as_ref().is_some_and(|c| c.contains("cargo")) {
+            let installed = installed_targets();
+            let (installed_failed, missing): (Vec<_>, Vec<_>) =
+                failed.iter().partition(|t| installed.contains(t.as_str()));
+
+            if !missing.is_empty() {
+                eprintln!("\nMissing targets (install with rustup):");
+                for t in &missing {
+                    eprintln!("  rustup target add {t}");
+                }
+            }
+            if !installed_failed.is_empty() {
+                eprintln!("\nInstalled but failed to build (missing cross-compilation linker):");
+                for t in &installed_failed {
+                    eprintln!("  - {t}");
+                }
+                eprintln!("  Tip: install `cross` (uses Docker) or `cargo-zigbuild` (uses zig) for cross-compilation");
+            }
+        }
+        if let Some(dir) = &config.build.pre_built_dir {
+            eprintln!("\nExpected pre-built artifacts in {dir}:");
+            for t in &failed {
+                eprintln!("  {dir}{binary}-{t}");
+            }
+        }
+        eprintln!();
+        let succeeded: Vec<&str> = archives.iter().map(|a| a.target.as_str()).collect();
+        eprintln!("Succeeded: {}", succeeded.join(", "));
+        if !confirm("Continue with successful targets?")? {
+            bail!("aborted by user");
+        }
+    }
+
+    Ok((archives, packages))
+}
+
+fn to_pascal_case(s: &str) -> String {
+    s.split(['-', '_'])
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => {
+                    let mut result = first.to_uppercase().to_string();
+                    result.extend(chars);
+                    result
+                }
+            }
+        })
+        .collect()
+}
+
+fn command_exists(cmd: &str) -> bool {
+    Command::new("sh")
+        .args(["-c", &format!("command -v {cmd}")])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Fails before anything is built when a selected channel's requirements aren't met, a channel it
+/// depends on isn't selected, or the `[signing]` tool or key is missing.
+fn preflight(selected: &[&dyn Channel], config: &Config, dry_run: bool) -> Result<()> {
+    let mut missing = Vec::new();
+
+    // A dry run makes no API calls and runs no publish tools
+    if !dry_run {
+        let mut needed: Vec<(Requirement, Vec<&str>)> = Vec::new();
+        for ch in selected {
+            for req in ch.requirements(config) {
+                match needed.iter_mut().find(|(r, _)| *r == req) {
+                    Some((_, users)) => users.push(ch.name()),
+                    None => needed.push((req, vec![ch.name()])),
+                }
+            }
+        }
+        for (req, users) in &needed {
+            if !req.is_satisfied() {
+                missing.push(format!("{req} is required for: {}", users.join(", ")));
+            }
+        }
+        // Only the github channel uploads signatures, so signing matters only when it is selected
+        if let Some(signing) = &config.signing
+            && selected.iter().any(|ch| ch.name() == "github")
+        {
+            missing.extend(sign::signing_problems(signing));
+        }
+    }
+
+    let mut unselected: Vec<(&str, Vec<&str>)> = Vec::new();
+    for ch in selected {
+        for dep in ch.depends_on() {
+            if selected.iter().any(|s| s.name() == dep) {
+                continue;
+            }
+            match unselected.iter_mut().find(|(d, _)| *d == dep) {
+                Some((_, users)) => users.push(ch.name()),
+                None => unselected.push((dep, vec![ch.name()])),
+            }
+        }
+    }
+    for (dep, users) in &unselected {
+        missing.push(format!("{dep} channel must be selected when using: {}", users.join(", ")));
+    }
+
+    if !missing.is_empty() {
+        bail!("preflight check failed:\n  - {}", missing.join("\n  - "));
+    }
+
+    Ok(())
+}
+
+/// Orders channels so each runs after the channels it depends on, otherwise keeping the given order.
+fn order<'a>(selected: &[&'a dyn Channel]) -> Vec<&'a dyn Channel> {
+    let mut remaining = selected.to_vec();
+    let mut ordered: Vec<&dyn Channel> = Vec::new();
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|ch| {
+            ch.depends_on()
+                .iter()
+                .all(|dep| !remaining.iter().any(|r| r.name() == *dep))
+        });
+        // A dependency cycle leaves nothing ready; fall back to the given order
+        ordered.push(remaining.remove(ready.unwrap_or(0)));
+    }
+    ordered
+}
+
+// --- Channels ---
+
+/// Something a channel needs from the environment before a release starts.
+#[derive(Debug, PartialEq)]
+pub enum Requirement {
+    Env(String),
+    Command(String),
+    /// A secret key in the gpg keyring
+    GpgKey(String),
+}
+
+impl Requirement {
+    fn is_satisfied(&self) -> bool {
+        match self {
+            Requirement::Env(var) => std::env::var(var).is_ok(),
+            Requirement::Command(cmd) => command_exists(cmd),
+            Requirement::GpgKey(key) => sign::gpg_secret_key_exists(key),
+        }
+    }
+}
+
+impl std::fmt::Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Requirement::Env(var) => write!(f, "{var} env var"),
+            Requirement::Command(cmd) => write!(f, "{cmd} command"),
+            Requirement::GpgKey(key) => write!(f, "GPG secret key {key}"),
+        }
+    }
+}
+
+/// The release being published, shared by every channel.
+pub struct Context<'a> {
+    pub config: &'a Config,
+    pub version: &'a str,
+    pub archives: &'a [Artifact],
+    /// Native packages uploaded next to the archives
+    pub packages: &'a [Package],
+    /// Names of the channels taking part in this release
+    pub channels: &'a [&'a str],
+}
+
+impl Context<'_> {
+    fn releases_to(&self, channel: &str) -> bool {
+        self.channels.contains(&channel)
+    }
+}
+
+/// Directory for files generated for one version (journal, manifests, scripts).
+fn staging_dir(version: &str) -> PathBuf {
+    Path::new("target/release-staging").join(version)
+}
+
+/// A release destination. Each channel lives in its own module and is listed in [`registry`].
+pub trait Channel {
+    /// Name used on the command line and as `[channels.<name>]`
+    fn name(&self) -> &str;
+
+    /// Channels that must be selected and run before this one
+    fn depends_on(&self) -> Vec<&str> {
+        Vec::new()
+    }
+
+    fn enabled(&self, config: &Config) -> bool;
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        Vec::new()
+    }
+
+    /// Renders everything the channel would publish, without side effects.
+    fn plan(&self, ctx: &Context) -> Result<Plan>;
+
+    fn publish(&self, ctx: &Context, journal: &mut Journal) -> Result<()>;
+}
+
+/// Every built-in channel, in default release order.
+pub fn builtin_channels() -> Vec<Box<dyn Channel>> {
+    vec![
+        Box::new(github::GitHub),
+        Box::new(homebrew::Homebrew),
+        Box::new(cargo::Cargo),
+        Box::new(curl::Curl),
+        Box::new(nix::Nix),
+        Box::new(scoop::Scoop),
+        Box::new(winget::Winget),
+        Box::new(aur::Aur),
+        Box::new(apt_repo::AptRepo),
+        Box::new(yum_repo::YumRepo),
+    ]
+}
+
+/// Built-in channels followed by the plugin channels declared in the config.
+pub fn registry(config: &Config) -> Vec<Box<dyn Channel>> {
+    let mut channels = builtin_channels();
+    for (name, ch) in &config.channels.plugin {
+        channels.push(Box::new(plugin::Plugin::new(name, ch)));
+    }
+    channels
+}
+
+fn github_token_requirement() -> Requirement {
+    Requirement::Env("GITHUB_TOKEN".to_string())
+}
+
+fn contents_request_body(
+    message: &str,
+    content: &str,
+    existing_sha: Option<String>,
+    branch: Option<&str>,
+) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "message": message,
+        "content": BASE64.encode(content.as_bytes()),
+    });
+    if let Some(sha) = existing_sha {
+        body["sha"] = serde_json::Value::String(sha);
+    }
+    if let Some(branch) = branch {
+        body["branch"] = serde_json::Value::String(branch.to_string());
+    }
+    body
+}
+
+/// Creates or updates `path` in `repo` through the contents API, on `branch` or the default branch.
+fn put_repo_file(label: &str, repo: &str, branch: Option<&str>, path: &str, content: &str, message: &str) -> Result<()> {
+    let api_url = format!("https://api.github.com/repos/{repo}/contents/{path}");
+    let get_url = match branch {
+        Some(branch) => format!("{api_url}?ref={branch}"),
+        None => api_url.clone(),
+    };
+    // the current blob sha is required to update an existing file
+    let existing_sha = crate::github::get_optional(label, &get_url)?
+        .and_then(|resp| resp["sha"].as_str().map(|s| s.to_string()));
+    let body = contents_request_body(message, content, existing_sha, branch);
+    crate::github::api(label, "PUT", &api_url, Some(&body))?;
+    Ok(())
+}
+
+/// A file committed to a tap or flake repository: `(path, content, commit message)`. The content
+/// is `None` in a plan when it is only known at release time.
+type RepoFile<'a> = (&'a str, Option<&'a str>, String);
+
+/// Branch that `update` commits `name` at `version` to, when it opens a pull request.
+fn update_branch(update: &RepoUpdate, name: &str, version: &str) -> Option<String> {
+    (update.mode == UpdateMode::PullRequest).then(|| format!("releasor2000/{name}-{version}"))
+}
+
+fn pull_request_body(config: &Config, version: &str, files: &[RepoFile]) -> String {
+    let paths: Vec<String> = files.iter().map(|(path, ..)| format!("`{path}`")).collect();
+    let (name, repo) = (&config.project.name, &config.project.repo);
+    format!(
+        "Updates {} for [{name} {version}](https://github.com/{repo}/releases/tag/v{version}).\n\nOpened by releasor2000.\n",
+        paths.join(", ")
+    )
+}
+
+/// Commits `files` to `repo`: on the default branch, or with `mode = "pull_request"` on a new
+/// branch that a pull request proposes to merge.
+fn update_repo_files(
+    label: &str,
+    config: &Config,
+    repo: &str,
+    update: &RepoUpdate,
+    name: &str,
+    version: &str,
+    files: &[RepoFile],
+) -> Result<()> {
+    let branch = update_branch(update, name, version);
+    let base = match &branch {
+        Some(branch) => Some(create_branch(label, repo, branch)?),
+        None => None,
+    };
+    for (path, content, message) in files {
+        put_repo_file(label, repo, branch.as_deref(), path, content.unwrap_or_default(), message)?;
+    }
+    if let (Some(branch), Some(base)) = (branch, base) {
+        let body = pull_request_body(config, version, files);
+        open_pull_request(label, repo, &branch, &base, &files[0].2, &body, update.auto_merge)?;
+    }
+    Ok(())
+}
+
+/// Creates `branch` from the head of the default branch unless it exists, and returns the
+/// default branch's name.
+fn create_branch(label: &str, repo: &str, branch: &str) -> Result<String> {
+    create_branch_with(label, repo, branch, |method, url, body| match method {
+        "GET" => crate::github::get_optional(label, url),
+        _ => crate::github::api(label, method, url, body).map(Some),
+    })
+}
+
+/// [`create_branch`] with its GitHub requests sent through `api`, which answers `None` for a 404.
+fn create_branch_with(
+    label: &str,
+    repo: &str,
+    branch: &str,
+    mut api: impl FnMut(&str, &str, Option<&serde_json::Value>) -> Result<Option<serde_json::Value>>,
+) -> Result<String> {
+    let repo_url = format!("https://api.github.com/repos/{repo}");
+    let not_found = |what: &str| anyhow::anyhow!("[{label}] {what} not found in {repo}");
+    let info = api("GET", &repo_url, None)?.ok_or_else(|| not_found("repository"))?;
+    let base = info["default_branch"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("[{label}] missing default branch for {repo}"))?
+        .to_string();
+    // `git/ref` (singular) matches exactly; `git/refs` would list every branch with this prefix
+    if api("GET", &format!("{repo_url}/git/ref/heads/{branch}"), None)?.is_some() {
+        return Ok(base);
+    }
+    let head = api("GET", &format!("{repo_url}/git/ref/heads/{base}"), None)?.ok_or_else(|| not_found(&base))?;
+    let sha = head["object"]["sha"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("[{label}] missing sha for {repo}@{base}"))?;
+    let body = serde_json::json!({ "ref": format!("refs/heads/{branch}"), "sha": sha });
+    api("POST", &format!("{repo_url}/git/refs"), Some(&body))?;
+    Ok(base)
+}
+
+const ENABLE_AUTO_MERGE: &str = "mutation($id: ID!, $method: PullRequestMergeMethod!) { \
+    enablePullRequestAutoMerge(input: { pullRequestId: $id, mergeMethod: $method }) { clientMutationId } }";
+
+/// Opens a pull request from `branch` into `base`, unless one is open already, and enables
+/// auto-merge on it.
+fn open_pull_request(
+    label: &str,
+    repo: &str,
+    branch: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+    auto_merge: Option<MergeMethod>,
+) -> Result<()> {
+    let owner = repo.split('/').next().unwrap_or(repo);
+    let pulls_url = format!("https://api.github.com/repos/{repo}/pulls");
+    let open = crate::github::api(label, "GET", &format!("{pulls_url}?head={owner}:{branch}&state=open"), None)?;
+    let pull = match open.as_array().and_then(|pulls| pulls.first()) {
+        Some(pull) => pull.clone(),
+        None => {
+            let request = serde_json::json!({ "title": title, "head": branch, "base": base, "body": body });
+            crate::github::api(label, "POST", &pulls_url, Some(&request))?
+        }
+    };
+    println!("[{label}] Pull request: {}", pull["html_url"].as_str().unwrap_or_default());
+    if let Some(method) = auto_merge {
+        let variables = serde_json::json!({ "id": pull["node_id"], "method": method.graphql_name() });
+        crate::github::graphql(label, ENABLE_AUTO_MERGE, variables)
+            .with_context(|| format!("[{label}] failed to enable auto-merge — is it allowed in {repo}?"))?;
+    }
+    Ok(())
+}
+
+/// Adds the requests [`update_repo_files`] makes to `plan`.
+fn plan_repo_files(plan: &mut Plan, config: &Config, repo: &str, update: &RepoUpdate, name: &str, version: &str, files: &[RepoFile]) {
+    let api_url = format!("https://api.github.com/repos/{repo}");
+    let branch = update_branch(update, name, version);
+    if let Some(branch) = &branch {
+        let body = serde_json::json!({ "ref": format!("refs/heads/{branch}"), "sha": format!("<{repo}@default branch>") });
+        plan.request("POST", format!("{api_url}/git/refs"), Some(body));
+    }
+    for (path, content, message) in files {
+        let body = content.map(|content| contents_request_body(message, content, None, branch.as_deref()));
+        plan.request("PUT", format!("{api_url}/contents/{path}"), body);
+    }
+    if let Some(branch) = &branch {
+        let body = pull_request_body(config, version, files);
+        let request = serde_json::json!({ "title": files[0].2, "head": branch, "base": "<default branch>", "body": body });
+        plan.request("POST", format!("{api_url}/pulls"), Some(request));
+        if let Some(method) = update.auto_merge {
+            let variables = serde_json::json!({ "id": "<pull request>", "method": method.graphql_name() });
+            plan.request("POST", crate::github::GRAPHQL_URL.to_string(), Some(serde_json::json!({ "query": ENABLE_AUTO_MERGE, "variables": variables })));
+        }
+    }
+}
+
+/// Lets git authenticate to GitHub over HTTPS with `GITHUB_TOKEN` without the token appearing
+/// on the command line.
+const GIT_TOKEN_CREDENTIALS: &str =
+    "credential.helper=!f() { echo username=x-access-token; echo \"password=$GITHUB_TOKEN\"; }; f";
+
+/// Runs `update` on the root of a self-hosted package repository. A `directory` is updated in
+/// place. A `branch` of `remote` is checked out, or started if it doesn't exist yet, updated
+/// under its `path` and pushed if anything changed.
+fn update_hosted_repo(
+    label: &str,
+    location: &HostedRepo,
+    remote: &str,
+    default_path: &str,
+    message: &str,
+    update: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    if let Some(directory) = &location.directory {
+        std::fs::create_dir_all(directory)?;
+        return update(Path::new(directory));
+    }
+    let branch = location.branch.as_deref().expect("validated: directory or branch is set");
+    let tmp_dir = std::env::temp_dir().join(format!("releasor2000-{label}-{}", std::process::id()));
+    if tmp_dir.exists() {
+        std::fs::remove_dir_all(&tmp_dir)?;
+    }
+    let tmp = tmp_dir.to_string_lossy();
+    let git = |dir: Option<&Path>, args: &[&str]| {
+        let mut full = vec!["-c", GIT_TOKEN_CREDENTIALS];
+        full.extend(args);
+        run_cmd(label, dir, "git", &full)
+    };
+    let head = format!("refs/heads/{branch}");
+    if git(None, &["ls-remote", remote, &head])?.is_empty() {
+        git(None, &["init", "-q", &tmp])?;
+        git(Some(&tmp_dir), &["checkout", "-q", "--orphan", branch])?;
+        git(Some(&tmp_dir), &["remote", "add", "origin", remote])?;
+    } else {
+        git(None, &["clone", "-q", "--depth", "1", "--branch", branch, remote, &tmp])?;
+    }
+
+    let root = tmp_dir.join(location.path.as_deref().unwrap_or(default_path));
+    std::fs::create_dir_all(&root)?;
+    update(&root)?;
+
+    let dir = Some(tmp_dir.as_path());
+    git(dir, &["add", "-A"])?;
+    if git(dir, &["status", "--porcelain"])?.is_empty() {
+        println!("[{label}] {branch} is already up to date");
+    } else {
+        // CI runners often have no git identity configured
+        let mut commit = vec!["commit", "-q", "-m", message];
+        if git(dir, &["config", "user.email"]).is_err() {
+            commit.splice(0..0, ["-c", "user.name=releasor2000", "-c", "user.email=releasor2000@users.noreply.github.com"]);
+        }
+        git(dir, &commit)?;
+        git(dir, &["push", "origin", &format!("HEAD:{head}")])?;
+        println!("[{label}] Pushed to {branch}");
+    }
+    std::fs::remove_dir_all(&tmp_dir).ok();
+    Ok(())
+}
+
+/// Every `*.<extension>` under `root` (when there is one) plus `new`, as paths relative to the
+/// repository root with their files, sorted. `new` wins over an existing file at the same path.
+fn hosted_packages(root: Option<&Path>, extension: &str, new: &[(String, PathBuf)]) -> Result<Vec<(String, PathBuf)>> {
+    let mut packages: Vec<(String, PathBuf)> = new.to_vec();
+    if let Some(root) = root {
+        let pattern = root.join("**").join(format!("*.{extension}"));
+        for path in glob::glob(&pattern.to_string_lossy())? {
+            let path = path?;
+            let relative = path.strip_prefix(root)?.to_string_lossy().replace('\\', "/");
+            if !packages.iter().any(|(p, _)| *p == relative) {
+                packages.push((relative, path));
+            }
+        }
+    }
+    packages.sort();
+    Ok(packages)
+}
+
+/// Gzips repository metadata without a timestamp, so unchanged indexes stay byte-identical.
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut gz = flate2::GzBuilder::new().mtime(0).write(Vec::new(), flate2::Compression::best());
+    std::io::Write::write_all(&mut gz, data)?;
+    Ok(gz.finish()?)
+}
+
+/// Copies this release's packages to their paths in the repository at `root`.
+fn copy_hosted_packages(root: &Path, new: &[(String, PathBuf)]) -> Result<()> {
+    for (relative, source) in new {
+        let dest = root.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(source, &dest).with_context(|| format!("copying {} to {}", source.display(), dest.display()))?;
+    }
+    Ok(())
+}
+
+// --- Public entry point ---
+
+pub fn release(
+    config: &Config,
+    version_override: Option<&str>,
+    channels: Option<&[String]>,
+    dry_run: bool,
+    resume: bool,
+) -> Result<()> {
+    let registry = registry(config);
+
+    let selected: Vec<&dyn Channel> = match channels {
+        Some(requested) => {
+            let mut selected = Vec::new();
+            for name in requested {
+                let Some(ch) = registry.iter().find(|c| c.name() == name) else {
+                    let known: Vec<&str> = registry.iter().map(|c| c.name()).collect();
+                    bail!("unknown channel: {name} (known: {})", known.join(", "));
+                };
+                if !ch.enabled(config) {
+                    bail!("channel {name} is not enabled in config");
+                }
+                selected.push(ch.as_ref());
+            }
+            selected
+        }
+        None => registry
+            .iter()
+            .map(|c| c.as_ref())
+            .filter(|c| c.enabled(config))
+            .collect(),
+    };
+
+    if selected.is_empty() {
+        println!("No channels enabled.");
+        return Ok(());
+    }
+
+    preflight(&selected, config, dry_run)?;
+
+    // Dependencies run first so later channels can reference e.g. release URLs
+    let ordered = order(&selected);
+    let names: Vec<&str> = ordered.iter().map(|c| c.name()).collect();
+
+    let version = detect_version(config, version_override)?;
+    println!(
+        "{} {} v{version} via: {}",
+        if dry_run { "Dry run of releasing" } else { "Releasing" },
+        config.project.name,
+        names.join(", ")
+    );
+
+    let mut journal = if dry_run {
+        None
+    } else {
+        Some(Journal::open(&version, resume)?)
+    };
+
+    let (archives, packages) = match journal.as_mut() {
+        Some(journal) if journal.has_archives() => {
+            println!("Reusing {} archives from the release journal", journal.archives.len());
+            (journal.archives.clone(), journal.packages.clone())
+        }
+        Some(journal) => {
+            let (archives, packages) = build_artifacts(config, &version)?;
+            journal.archives = archives.clone();
+            journal.packages = packages.clone();
+            journal.save()?;
+            (archives, packages)
+        }
+        None => build_artifacts(config, &version)?,
+    };
+
+    let ctx = Context {
+        config,
+        version: &version,
+        archives: &archives,
+        packages: &packages,
+        channels: &names,
+    };
+
+    let Some(journal) = journal.as_mut() else {
+        return dry_run_release(&ctx, &ordered);
+    };
+
+    for channel in &ordered {
+        let name = channel.name();
+        if journal.is_done(name) {
+            println!("[{name}] Already completed, skipping");
+            continue;
+        }
+        channel.publish(&ctx, journal)?;
+        journal.complete(name)?;
+    }
+
+    println!("Done.");
+    Ok(())
+}
+
+// --- Release journal ---
+
+/// Progress of a release, persisted after every step so a failed release can be resumed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(skip)]
+    path: PathBuf,
+    version: String,
+    /// Archives produced by the build, reused on resume
+    #[serde(default)]
+    archives: Vec<Artifact>,
+    /// Packages produced by the build, reused on resume
+    #[serde(default)]
+    packages: Vec<Package>,
+    /// Upload URL of the GitHub release once it has been created
+    #[serde(default)]
+    upload_url: Option<String>,
+    /// Completed steps: channel names, plus `<channel>/<step>` for steps within a channel
+    #[serde(default)]
+    completed: Vec<String>,
+}
+
+impl Journal {
+    fn path_for(version: &str) -> PathBuf {
+        staging_dir(version).join("state.json")
+    }
+
+    /// Starts a new journal, or loads the existing one when resuming. A journal of a release
+    /// that never published anything is started over; it is first saved once the build succeeds.
+    fn open(version: &str, resume: bool) -> Result<Self> {
+        let path = Self::path_for(version);
+        if path.exists() {
+            let content = std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            let mut journal: Journal =
+                serde_json::from_str(&content).with_context(|| format!("parsing {}", path.display()))?;
+            journal.path = path.clone();
+            if resume {
+                println!("Resuming release v{version} from {}", journal.path.display());
+                return Ok(journal);
+            }
+            if journal.has_published() {
+                bail!(
+                    "a release journal for v{version} already exists at {} — pass --resume to continue it, or delete it to start over",
+                    journal.path.display()
+                );
+            }
+        } else if resume {
+            println!("No release journal found at {}, starting from the beginning", path.display());
+        }
+        Ok(Self {
+            path,
+            version: version.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Whether anything has left this machine: a created release or a completed step.
+    fn has_published(&self) -> bool {
+        self.upload_url.is_some() || !self.completed.is_empty()
+    }
+
+    fn has_archives(&self) -> bool {
+        !self.archives.is_empty()
+            && self.archives.iter().all(|a| a.path.exists())
+            && self.packages.iter().all(|p| p.path.exists())
+    }
+
+    fn is_done(&self, step: &str) -> bool {
+        self.completed.iter().any(|s| s == step)
+    }
+
+    fn complete(&mut self, step: &str) -> Result<()> {
+        if !self.is_done(step) {
+            self.completed.push(step.to_string());
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", self.path.display()))
+    }
+}
+
+// --- Dry run ---
+
+const DRY_RUN_DIR: &str = "target/release-staging/dry-run";
+
+/// A side effect a channel would perform, recorded instead of executed in dry-run mode.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PlannedStep {
+    Request {
+        method: String,
+        url: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        body: Option<serde_json::Value>,
+    },
+    Upload {
+        url: String,
+        file: PathBuf,
+        content_type: String,
+    },
+    Command {
+        program: String,
+        args: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for PlannedStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlannedStep::Request { method, url, body } => {
+                write!(f, "{method} {url}")?;
+                if body.is_none() {
+                    write!(f, " (body generated at publish time)")?;
+                }
+                Ok(())
+            }
+            PlannedStep::Upload { url, file, .. } => write!(f, "UPLOAD {} -> {url}", file.display()),
+            PlannedStep::Command { program, args } => write!(f, "RUN {program} {}", args.join(" ")),
+        }
+    }
+}
+
+/// Everything a channel would write and send, rendered without touching the network.
+#[derive(Debug, Serialize)]
+pub struct Plan {
+    channel: String,
+    /// Rendered files, relative to the channel's dry-run directory
+    files: Vec<PathBuf>,
+    steps: Vec<PlannedStep>,
+    #[serde(skip)]
+    contents: Vec<String>,
+}
+
+impl Plan {
+    fn new(channel: &str) -> Self {
+        Self {
+            channel: channel.to_string(),
+            files: Vec::new(),
+            steps: Vec::new(),
+            contents: Vec::new(),
+        }
+    }
+
+    fn dir(&self) -> PathBuf {
+        Path::new(DRY_RUN_DIR).join(&self.channel)
+    }
+
+    /// Adds a rendered file and returns the path it will be written to.
+    fn file(&mut self, path: &str, content: String) -> PathBuf {
+        self.files.push(PathBuf::from(path));
+        self.contents.push(content);
+        self.dir().join(path)
+    }
+
+    fn request(&mut self, method: &str, url: String, body: Option<serde_json::Value>) {
+        self.steps.push(PlannedStep::Request {
+            method: method.to_string(),
+            url,
+            body,
+        });
+    }
+
+    fn upload(&mut self, repo: &str, file: PathBuf, content_type: &str) {
+        let name = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        self.steps.push(PlannedStep::Upload {
+            url: format!("https://uploads.github.com/repos/{repo}/releases/{{release_id}}/assets?name={name}"),
+            file,
+            content_type: content_type.to_string(),
+        });
+    }
+
+    fn command(&mut self, program: &str, args: &[&str]) {
+        self.steps.push(PlannedStep::Command {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        });
+    }
+
+    fn write(&self) -> Result<()> {
+        for (path, content) in self.files.iter().zip(&self.contents) {
+            let dest = self.dir().join(path);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&dest, content).with_context(|| format!("writing {}", dest.display()))?;
+        }
+        Ok(())
+    }
+}
+
+fn dry_run_release(ctx: &Context, channels: &[&dyn Channel]) -> Result<()> {
+    let dir = Path::new(DRY_RUN_DIR);
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    std::fs::create_dir_all(dir)?;
+
+    let mut plans = Vec::new();
+    for channel in channels {
+        let plan = channel.plan(ctx)?;
+        plan.write()?;
+        plans.push(plan);
+    }
+
+    let summary = serde_json::json!({
+        "project": ctx.config.project.name,
+        "version": ctx.version,
+        "archives": ctx.archives,
+        "packages": ctx.packages,
+        "channels": plans,
+    });
+    let plan_path = dir.join("plan.json");
+    std::fs::write(&plan_path, serde_json::to_string_pretty(&summary)?)?;
+
+    println!("\nArchives:");
+    for archive in ctx.archives {
+        println!("  {}  sha256:{}", archive.path.display(), archive.sha256());
+    }
+    for package in ctx.packages {
+        println!("  {}  sha256:{}", package.path.display(), package.digests.sha256);
+    }
+    for plan in &plans {
+        println!("\n[{}]", plan.channel);
+        for file in &plan.files {
+            println!("  WRITE {}", plan.dir().join(file).display());
+        }
+        for step in &plan.steps {
+            println!("  {step}");
+        }
+    }
+    println!("\nDry run complete, nothing was published. Plan written to {}", plan_path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- substitute tests ---
+
+    #[test]
+    fn substitute_basic_replacement() {
+        let result = substitute("hello {name}", &[("name", "world")]);
+        assert_eq!(result, "hello world");
+    }
+
+    #[test]
+    fn substitute_no_op_when_no_match() {
+        let result = substitute("no placeholders here", &[("name", "world")]);
+        assert_eq!(result, "no placeholders here");
+    }
+
+    #[test]
+    fn substitute_multiple_occurrences() {
+        let result = substitute("{x} and {x} and {y}", &[("x", "a"), ("y", "b")]);
+        assert_eq!(result, "a and a and b");
+    }
+
+    // --- to_pascal_case tests ---
+
+    #[test]
+    fn to_pascal_case_hyphenated() {
+        assert_eq!(to_pascal_case("my-cool-tool"), "MyCoolTool");
+    }
+
+    #[test]
+    fn to_pascal_case_underscored() {
+        assert_eq!(to_pascal_case("my_cool_tool"), "MyCoolTool");
+    }
+
+    #[test]
+    fn to_pascal_case_single_word() {
+        assert_eq!(to_pascal_case("hello"), "Hello");
+    }
+
+    // --- exe_suffix tests ---
+
+    #[test]
+    fn exe_suffix_only_for_windows() {
+        assert_eq!(exe_suffix("x86_64-pc-windows-msvc"), ".exe");
+        assert_eq!(exe_suffix("aarch64-pc-windows-gnullvm"), ".exe");
+        assert_eq!(exe_suffix("x86_64-unknown-linux-gnu"), "");
+    }
+
+    // --- parse_host_target tests ---
+
+    #[test]
+    fn parse_host_target_extracts_host_line() {
+        let output = "rustc 1.77.0 (aedd173a2 2024-03-17)\nbinary: rustc\nhost: aarch64-apple-darwin\nrelease: 1.77.0\n";
+        assert_eq!(
+            parse_host_target(output),
+            Some("aarch64-apple-darwin".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_host_target_missing() {
+        assert_eq!(parse_host_target("no host here\n"), None);
+    }
+
+    // --- needs_cross_linker tests ---
+
+    #[test]
+    fn needs_cross_linker_same_os_different_arch() {
+        assert!(!needs_cross_linker(
+            "aarch64-apple-darwin",
+            "x86_64-apple-darwin"
+        ));
+    }
+
+    #[test]
+    fn needs_cross_linker_different_os() {
+        assert!(needs_cross_linker(
+            "aarch64-apple-darwin",
+            "x86_64-unknown-linux-gnu"
+        ));
+    }
+
+    #[test]
+    fn needs_cross_linker_identical() {
+        assert!(!needs_cross_linker(
+            "aarch64-apple-darwin",
+            "aarch64-apple-darwin"
+        ));
+    }
+
+    #[test]
+    fn needs_cross_linker_linux_different_arch() {
+        assert!(needs_cross_linker(
+            "x86_64-unknown-linux-gnu",
+            "aarch64-unknown-linux-gnu"
+        ));
+    }
+
+    // --- parse_installed_targets tests ---
+
+    #[test]
+    fn parse_installed_targets_typical_output() {
+        let output = "aarch64-apple-darwin\nx86_64-apple-darwin\nx86_64-unknown-linux-gnu\n";
+        let result = parse_installed_targets(output);
+        assert_eq!(result.len(), 3);
+        assert!(result.contains("aarch64-apple-darwin"));
+        assert!(result.contains("x86_64-apple-darwin"));
+        assert!(result.contains("x86_64-unknown-linux-gnu"));
+    }
+
+    #[test]
+    fn parse_installed_targets_empty_output() {
+        assert!(parse_installed_targets("").is_empty());
+        assert!(parse_installed_targets("  \n  \n").is_empty());
+    }
+
+    #[test]
+    fn parse_installed_targets_trims_whitespace() {
+        let output = "  aarch64-apple-darwin  \n  x86_64-apple-darwin \n";
+        let result = parse_installed_targets(output);
+        assert_eq!(result.len(), 2);
+        assert!(result.contains("aarch64-apple-darwin"));
+        assert!(result.contains("x86_64-apple-darwin"));
+    }
+
+    // --- order tests ---
+
+    #[test]
+    fn order_runs_dependencies_first() {
+        let registry = builtin_channels();
+        let by_name = |name: &str| registry.iter().find(|c| c.name() == name).unwrap().as_ref();
+        let selected = vec![by_name("cargo"), by_name("homebrew"), by_name("github"), by_name("nix")];
+        let names: Vec<&str> = order(&selected).iter().map(|c| c.name()).collect();
+        assert_eq!(names, vec!["cargo", "github", "homebrew", "nix"]);
+    }
+
+    #[test]
+    fn registry_names_are_unique() {
+        let names: Vec<String> = builtin_channels().iter().map(|c| c.name().to_string()).collect();
+        let unique: HashSet<&String> = names.iter().collect();
+        assert_eq!(unique.len(), names.len());
+    }
+
+    // --- dry run tests ---
+
+    #[test]
+    fn plan_upload_targets_release_assets() {
+        let mut plan = Plan::new("github");
+        plan.upload("owner/repo", PathBuf::from("target/release-staging/tool-1.0.0-x.tar.gz"), "application/gzip");
+        let step = plan.steps[0].to_string();
+        assert_eq!(
+            step,
+            "UPLOAD target/release-staging/tool-1.0.0-x.tar.gz -> https://uploads.github.com/repos/owner/repo/releases/{release_id}/assets?name=tool-1.0.0-x.tar.gz"
+        );
+    }
+
+    #[test]
+    fn plan_file_is_written_under_channel_dir() {
+        let mut plan = Plan::new("curl");
+        let path = plan.file("install.sh", "#!/bin/sh".to_string());
+        assert_eq!(path, Path::new(DRY_RUN_DIR).join("curl").join("install.sh"));
+        assert_eq!(plan.files, vec![PathBuf::from("install.sh")]);
+    }
+
+    #[test]
+    fn contents_request_body_includes_existing_sha() {
+        let body = contents_request_body("msg", "hi", Some("abc".to_string()), None);
+        assert_eq!(body["message"], "msg");
+        assert_eq!(body["content"], "aGk=");
+        assert_eq!(body["sha"], "abc");
+        assert!(contents_request_body("msg", "hi", None, None).get("sha").is_none());
+        assert!(contents_request_body("msg", "hi", None, None).get("branch").is_none());
+        assert_eq!(contents_request_body("msg", "hi", None, Some("topic"))["branch"], "topic");
+    }
+
+    fn pull_request_config() -> Config {
+        let toml = "[project]\nname = \"tool\"\nrepo = \"owner/tool\"\n\n[build]\npre_built_dir = \"dist\"\ntargets = [\"x86_64-unknown-linux-gnu\"]\n\n[channels.homebrew]\ntap = \"owner/homebrew-tap\"\nmode = \"pull_request\"\nauto_merge = \"squash\"\n";
+        Config::parse(toml).unwrap()
+    }
+
+    #[test]
+    fn plan_repo_files_commits_to_default_branch() {
+        let mut plan = Plan::new("homebrew");
+        let files = [("Formula/tool.rb", Some("class Tool"), "Update tool to 1.0.0".to_string())];
+        plan_repo_files(&mut plan, &pull_request_config(), "owner/tap", &RepoUpdate::default(), "tool", "1.0.0", &files);
+        let steps: Vec<String> = plan.steps.iter().map(ToString::to_string).collect();
+        assert_eq!(steps, vec!["PUT https://api.github.com/repos/owner/tap/contents/Formula/tool.rb"]);
+    }
+
+    #[test]
+    fn plan_repo_files_opens_pull_request() {
+        let config = pull_request_config();
+        let update = &config.channels.homebrew.as_ref().unwrap().update;
+        assert_eq!(update_branch(update, "tool", "1.0.0").as_deref(), Some("releasor2000/tool-1.0.0"));
+
+        let mut plan = Plan::new("homebrew");
+        let files = [
+            ("flake.nix", Some("{}"), "Update tool to 1.0.0".to_string()),
+            ("flake.lock", None, "Update flake.lock for tool 1.0.0".to_string()),
+        ];
+        plan_repo_files(&mut plan, &config, "owner/tap", update, "tool", "1.0.0", &files);
+        let steps: Vec<String> = plan.steps.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            steps,
+            vec![
+                "POST https://api.github.com/repos/owner/tap/git/refs",
+                "PUT https://api.github.com/repos/owner/tap/contents/flake.nix",
+                "PUT https://api.github.com/repos/owner/tap/contents/flake.lock (body generated at publish time)",
+                "POST https://api.github.com/repos/owner/tap/pulls",
+                "POST https://api.github.com/graphql",
+            ]
+        );
+        let PlannedStep::Request { body: Some(put), .. } = &plan.steps[1] else { panic!("expected a PUT body") };
+        assert_eq!(put["branch"], "releasor2000/tool-1.0.0");
+        let PlannedStep::Request { body: Some(pull), .. } = &plan.steps[3] else { panic!("expected a pull request body") };
+        assert_eq!(pull["title"], "Update tool to 1.0.0");
+        assert_eq!(pull["head"], "releasor2000/tool-1.0.0");
+        assert_eq!(
+            pull["body"],
+            "Updates `flake.nix`, `flake.lock` for [tool 1.0.0](https://github.com/owner/tool/releases/tag/v1.0.0).\n\nOpened by releasor2000.\n"
+        );
+        let PlannedStep::Request { body: Some(merge), .. } = &plan.steps[4] else { panic!("expected a GraphQL body") };
+        assert_eq!(merge["variables"]["method"], "SQUASH");
+    }
+
+    /// Answers `create_branch_with` like GitHub would for a repo whose branches are `existing`,
+    /// recording every request.
+    fn fake_refs_api<'a>(
+        existing: &'a [&'a str],
+        requests: &'a mut Vec<String>,
+    ) -> impl FnMut(&str, &str, Option<&serde_json::Value>) -> Result<Option<serde_json::Value>> + 'a {
+        move |method, url, _| {
+            requests.push(format!("{method} {url}"));
+            if url == "https://api.github.com/repos/owner/tap" {
+                return Ok(Some(serde_json::json!({ "default_branch": "main" })));
+            }
+            let exists = url
+                .strip_prefix("https://api.github.com/repos/owner/tap/git/ref/heads/")
+                .is_some_and(|branch| existing.contains(&branch));
+            Ok(exists.then(|| serde_json::json!({ "object": { "sha": "abc123" } })))
+        }
+    }
+
+    #[test]
+    fn create_branch_reuses_existing_branch() {
+        let mut requests = Vec::new();
+        let existing = ["main", "releasor2000/tool-1.0.0"];
+        let base = create_branch_with("homebrew", "owner/tap", "releasor2000/tool-1.0.0", fake_refs_api(&existing, &mut requests));
+        assert_eq!(base.unwrap(), "main");
+        assert_eq!(
+            requests,
+            vec![
+                "GET https://api.github.com/repos/owner/tap",
+                "GET https://api.github.com/repos/owner/tap/git/ref/heads/releasor2000/tool-1.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn create_branch_starts_branch_from_default_branch() {
+        let mut requests = Vec::new();
+        // a release candidate's branch shares the prefix but isn't this branch
+        let existing = ["main", "releasor2000/tool-1.0.0-rc.1"];
+        let base = create_branch_with("homebrew", "owner/tap", "releasor2000/tool-1.0.0", fake_refs_api(&existing, &mut requests));
+        assert_eq!(base.unwrap(), "main");
+        assert_eq!(
+            requests,
+            vec![
+                "GET https://api.github.com/repos/owner/tap",
+                "GET https://api.github.com/repos/owner/tap/git/ref/heads/releasor2000/tool-1.0.0",
+                "GET https://api.github.com/repos/owner/tap/git/ref/heads/main",
+                "POST https://api.github.com/repos/owner/tap/git/refs",
+            ]
+        );
+    }
+
+    // --- journal tests ---
+
+    fn archive(path: &str) -> Artifact {
+        Artifact {
+            target: "x86_64-apple-darwin".to_string(),
+            path: PathBuf::from(path),
+            format: Default::default(),
+            files: Vec::new(),
+            digests: Default::default(),
+        }
+    }
+
+    #[test]
+    fn journal_roundtrip_preserves_progress() {
+        let journal = Journal {
+            path: PathBuf::from("unused"),
+            version: "1.0.0".to_string(),
+            archives: vec![archive("a.tar.gz")],
+            packages: vec![Package {
+                target: "x86_64-unknown-linux-gnu".to_string(),
+                format: package::PackageFormat::Deb,
+                path: PathBuf::from("a_1.0.0_amd64.deb"),
+                digests: Default::default(),
+            }],
+            upload_url: Some("https://uploads.github.com/repos/o/r/releases/1/assets".to_string()),
+            completed: vec!["github/a.tar.gz".to_string()],
+        };
+        let json = serde_json::to_string(&journal).unwrap();
+        let loaded: Journal = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.version, "1.0.0");
+        assert_eq!(loaded.archives, journal.archives);
+        assert_eq!(loaded.packages, journal.packages);
+        assert_eq!(loaded.upload_url, journal.upload_url);
+        assert!(loaded.is_done("github/a.tar.gz"));
+        assert!(!loaded.is_done("github"));
+    }
+
+    #[test]
+    fn journal_has_archives_requires_files_on_disk() {
+        let mut journal = Journal::default();
+        assert!(!journal.has_archives());
+        journal.archives = vec![archive("does/not/exist.tar.gz")];
+        assert!(!journal.has_archives());
+        journal.archives = vec![archive("Cargo.toml")];
+        assert!(journal.has_archives());
+    }
+
+    #[test]
+    fn journal_has_published_after_release_or_step() {
+        let mut journal = Journal {
+            archives: vec![archive("a.tar.gz")],
+            ..Default::default()
+        };
+        assert!(!journal.has_published());
+        journal.upload_url = Some("https://uploads.github.com/repos/o/r/releases/1/assets".to_string());
+        assert!(journal.has_published());
+        journal.upload_url = None;
+        journal.completed.push("homebrew".to_string());
+        assert!(journal.has_published());
+    }
+
+    #[test]
+    fn journal_path_is_per_version() {
+        assert_eq!(
+            Journal::path_for("1.2.3"),
+            PathBuf::from("target/release-staging/1.2.3/state.json")
+        );
+    }
+
+    // --- preflight tests ---
+
+    const PREFLIGHT_TOML: &str = "[project]\nname = \"tool\"\nrepo = \"owner/tool\"\n\n[build]\npre_built_dir = \"dist\"\ntargets = [\"x86_64-unknown-linux-gnu\"]\n";
+
+    fn preflight_with(names: &[&str], toml: &str, dry_run: bool) -> Result<()> {
+        let config = Config::parse(toml).unwrap();
+        let registry = builtin_channels();
+        let selected: Vec<&dyn Channel> = names
+            .iter()
+            .map(|name| registry.iter().find(|c| c.name() == *name).unwrap().as_ref())
+            .collect();
+        preflight(&selected, &config, dry_run)
+    }
+
+    fn preflight_for(names: &[&str], dry_run: bool) -> Result<()> {
+        preflight_with(names, PREFLIGHT_TOML, dry_run)
+    }
+
+    #[test]
+    fn preflight_ok_with_no_channels() {
+        assert!(preflight_for(&[], false).is_ok());
+    }
+
+    #[test]
+    fn preflight_requires_github_token() {
+        // Remove GITHUB_TOKEN to ensure the check triggers
+        let saved = std::env::var("GITHUB_TOKEN").ok();
+        unsafe { std::env::remove_var("GITHUB_TOKEN") };
+
+        let err = preflight_for(&["github"], false).unwrap_err();
+        assert!(err.to_string().contains("GITHUB_TOKEN"), "got: {err}");
+
+        if let Some(val) = saved {
+            unsafe { std::env::set_var("GITHUB_TOKEN", val) };
+        }
+    }
+
+    #[test]
+    fn preflight_requires_nix() {
+        // This test assumes `nix` is not installed in the test environment,
+        // which is typical for CI. If nix IS installed, we can't test the
+        // negative case, so skip.
+        if command_exists("nix") {
+            return;
+        }
+
+        // Ensure GITHUB_TOKEN is set so that check doesn't also fail
+        let saved = std::env::var("GITHUB_TOKEN").ok();
+        unsafe { std::env::set_var("GITHUB_TOKEN", "fake-token-for-test") };
+
+        let err = preflight_for(&["github", "nix"], false).unwrap_err();
+        assert!(err.to_string().contains("nix command"), "got: {err}");
+
+        match saved {
+            Some(val) => unsafe { std::env::set_var("GITHUB_TOKEN", val) },
+            None => unsafe { std::env::remove_var("GITHUB_TOKEN") },
+        }
+    }
+
+    #[test]
+    fn preflight_dry_run_skips_token_check() {
+        let saved = std::env::var("GITHUB_TOKEN").ok();
+        unsafe { std::env::remove_var("GITHUB_TOKEN") };
+
+        assert!(preflight_for(&["github", "homebrew"], true).is_ok());
+        let err = preflight_for(&["homebrew"], true).unwrap_err();
+        assert!(err.to_string().contains("github channel must be selected"), "got: {err}");
+
+        if let Some(val) = saved {
+            unsafe { std::env::set_var("GITHUB_TOKEN", val) };
+        }
+    }
+
+    #[test]
+    fn preflight_requires_github_for_dependent_channels() {
+        // Ensure GITHUB_TOKEN is set so only the dependency check triggers
+        let saved = std::env::var("GITHUB_TOKEN").ok();
+        unsafe { std::env::set_var("GITHUB_TOKEN", "fake-token-for-test") };
+
+        for ch in &["homebrew", "curl", "nix"] {
+            let err = preflight_for(&[*ch], false).unwrap_err();
+            assert!(
+                err.to_string().contains("github channel must be selected"),
+                "channel {ch}: got: {err}"
+            );
+        }
+
+        match saved {
+            Some(val) => unsafe { std::env::set_var("GITHUB_TOKEN", val) },
+            None => unsafe { std::env::remove_var("GITHUB_TOKEN") },
+        }
+    }
+
+    #[test]
+    fn preflight_checks_signing_key_before_building() {
+        if !command_exists("ssh-keygen") {
+            return;
+        }
+        let saved = std::env::var("GITHUB_TOKEN").ok();
+        unsafe { std::env::set_var("GITHUB_TOKEN", "fake-token-for-test") };
+
+        let toml = format!("{PREFLIGHT_TOML}\n[signing]\nmethod = \"ssh\"\nkey = \"/nonexistent/release.key\"\n");
+        let err = preflight_with(&["github"], &toml, false).unwrap_err();
+        assert!(err.to_string().contains("signing key /nonexistent/release.key not found"), "got: {err}");
+        // nothing is signed in a dry run, or without the github channel
+        assert!(preflight_with(&["github"], &toml, true).is_ok());
+        assert!(preflight_with(&[], &toml, false).is_ok());
+
+        match saved {
+            Some(val) => unsafe { std::env::set_var("GITHUB_TOKEN", val) },
+            None => unsafe { std::env::remove_var("GITHUB_TOKEN") },
+        }
+    }
+
+    #[test]
+    fn preflight_checks_repository_gpg_key() {
+        if !command_exists("git") {
+            return;
+        }
+        let unsigned = format!(
+            "{PREFLIGHT_TOML}\n[packages.deb]\nmaintainer = \"Me <me@example.com>\"\n\n[channels.apt-repo]\ndirectory = \"repo\"\n"
+        );
+        assert!(preflight_with(&["apt-repo"], &unsigned, false).is_ok());
+
+        let key = "releasor2000-no-such-key@example.invalid";
+        let signed = format!("{unsigned}gpg_key = \"{key}\"\n");
+        let err = preflight_with(&["apt-repo"], &signed, false).unwrap_err().to_string();
+        assert!(err.contains(&format!("GPG secret key {key} is required for: apt-repo")), "got: {err}");
+        if !command_exists("gpg") {
+            assert!(err.contains("gpg command is required for: apt-repo"), "got: {err}");
+        }
+        // a dry run signs nothing
+        assert!(preflight_with(&["apt-repo"], &signed, true).is_ok());
+    }
+
+    // --- hosted repo tests ---
+
+    #[test]
+    fn update_hosted_repo_starts_and_appends_to_branch() {
+        let dir = std::env::temp_dir().join(format!("releasor2000-hosted-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let remote = dir.join("remote.git");
+        let remote = remote.to_string_lossy();
+        run_cmd("test", None, "git", &["init", "-q", "--bare", &remote]).unwrap();
+        let location = HostedRepo {
+            directory: None,
+            branch: Some("gh-pages".to_string()),
+            path: None,
+        };
+        for version in ["1.0.0", "1.1.0"] {
+            update_hosted_repo("test", &location, &remote, "apt", "Add", |root| {
+                std::fs::write(root.join(format!("{version}.deb")), version)?;
+                Ok(())
+            })
+            .unwrap();
+        }
+        let listing = run_cmd("test", None, "git", &["--git-dir", &remote, "ls-tree", "-r", "--name-only", "gh-pages"]).unwrap();
+        assert_eq!(listing, "apt/1.0.0.deb\napt/1.1.0.deb");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn hosted_packages_merges_existing_and_new() {
+        let dir = std::env::temp_dir().join(format!("releasor2000-hosted-packages-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("Packages")).unwrap();
+        std::fs::write(dir.join("Packages/tool-1.0.0-1.x86_64.rpm"), "old").unwrap();
+        std::fs::write(dir.join("Packages/tool-1.1.0-1.x86_64.rpm"), "stale").unwrap();
+        let new = vec![("Packages/tool-1.1.0-1.x86_64.rpm".to_string(), PathBuf::from("staging/tool.rpm"))];
+        assert_eq!(
+            hosted_packages(Some(&dir), "rpm", &new).unwrap(),
+            vec![
+                ("Packages/tool-1.0.0-1.x86_64.rpm".to_string(), dir.join("Packages/tool-1.0.0-1.x86_64.rpm")),
+                ("Packages/tool-1.1.0-1.x86_64.rpm".to_string(), PathBuf::from("staging/tool.rpm")),
+            ]
+        );
+        assert_eq!(hosted_packages(None, "rpm", &new).unwrap(), new);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,369 @@
+use anyhow::{Context as _, Result};
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, archive_prefix, github_token_requirement, plan_repo_files, run_cmd,
+    update_repo_files,
+};
+use crate::artifact::Artifact;
+use crate::config::Config;
+use crate::extras::{completions, man_pages};
+use crate::github;
+
+/// Pushes a `flake.nix` and `flake.lock` that fetch the release archives.
+pub struct Nix;
+
+impl Channel for Nix {
+    fn name(&self) -> &str {
+        "nix"
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["github"]
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.nix.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![github_token_requirement(), Requirement::Command("nix".to_string())]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_nix(ctx.config, ctx.version, ctx.archives)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_nix(ctx.config, ctx.version, ctx.archives)
+    }
+}
+
+fn nix_system(target: &str) -> Option<&'static str> {
+    if target.contains("x86_64") && target.contains("linux") {
+        Some("x86_64-linux")
+    } else if target.contains("aarch64") && target.contains("linux") {
+        Some("aarch64-linux")
+    } else if target.contains("x86_64") && target.contains("darwin") {
+        Some("x86_64-darwin")
+    } else if target.contains("aarch64") && target.contains("darwin") {
+        Some("aarch64-darwin")
+    } else {
+        None
+    }
+}
+
+/// The first of `binaries` names the package.
+fn generate_flake(
+    name: &str,
+    binaries: &[&str],
+    repo: &str,
+    version: &str,
+    // (nix system, asset name, sha256, directory the archive unpacks into)
+    system_hashes: &[(&str, &str, &str, &str)],
+    files: &[String],
+) -> String {
+    let binary = binaries.first().copied().unwrap_or(name);
+    let mut install: Vec<String> = binaries.iter().map(|b| format!("install -m755 -D {b} $out/bin/{b}")).collect();
+    for (shell, path, command) in completions(files) {
+        install.push(format!("installShellCompletion --cmd {command} --{} {path}", shell.name()));
+    }
+    for (_, path) in man_pages(files) {
+        install.push(format!("installManPage {path}"));
+    }
+    let install = install.join("\n            ");
+
+    let pkg_entries: Vec<String> = system_hashes
+        .iter()
+        .map(|(nix_sys, asset_name, sha256_hex, source_root)| {
+            // stdenv unpacks tar.gz and tar.xz on its own
+            let mut inputs = Vec::new();
+            if asset_name.ends_with(".zip") {
+                inputs.push("pkgs.unzip");
+            } else if asset_name.ends_with(".tar.zst") {
+                inputs.push("pkgs.zstd");
+            }
+            if install.contains("installShellCompletion") || install.contains("installManPage") {
+                inputs.push("pkgs.installShellFiles");
+            }
+            let native_inputs = if inputs.is_empty() {
+                String::new()
+            } else {
+                format!("\n          nativeBuildInputs = [ {} ];", inputs.join(" "))
+            };
+            // rendered in one pass, so no value is mistaken for a placeholder
+            format!(
+                r#"      "{nix_sys}" = let
+        pkgs = nixpkgs.legacyPackages.{nix_sys};
+        pkg = pkgs.stdenv.mkDerivation {{
+          pname = "{binary}";
+          version = "{version}";
+          src = pkgs.fetchurl {{
+            url = "https://github.com/{repo}/releases/download/v{version}/{asset_name}";
+            sha256 = "{sha256_hex}";
+          }};
+          sourceRoot = "{source_root}";{native_inputs}
+          installPhase = ''
+            {install}
+          '';
+        }};
+      in {{ {binary} = pkg; default = pkg; }};"#
+            )
+        })
+        .collect();
+    let pkg_entries = pkg_entries.join("\n");
+
+    format!(
+        r#"{{
+  description = "{name}";
+
+  inputs.nixpkgs.url = "github:NixOS/nixpkgs/nixpkgs-unstable";
+
+  outputs = {{ self, nixpkgs }}: {{
+    packages = {{
+{pkg_entries}
+    }};
+  }};
+}}
+"#
+    )
+}
+
+fn release_nix(
+    config: &Config,
+    version: &str,
+    archives: &[Artifact],
+) -> Result<()> {
+    let ch = config.channels.nix.as_ref().unwrap();
+    let binary = config.project.binary();
+    let repo = &config.project.repo;
+    let flake_repo = ch.flake_repo.as_deref().unwrap_or(repo);
+
+    // Local archives are reproducible, so their digests match the uploaded assets
+    let release_url = format!("https://api.github.com/repos/{repo}/releases/tags/v{version}");
+    let release = github::api("nix", "GET", &release_url, None)
+        .with_context(|| format!("[nix] GitHub release v{version} not found — run the github channel first"))?;
+
+    let mut system_hashes = Vec::new();
+    for archive in archives {
+        let nix_sys = match nix_system(&archive.target) {
+            Some(s) => s,
+            None => continue,
+        };
+        let asset_name = archive.name();
+
+        // Verify asset exists in the release
+        let assets = release["assets"].as_array();
+        let asset_exists = assets.is_some_and(|a| {
+            a.iter().any(|asset| asset["name"].as_str() == Some(&asset_name))
+        });
+        if !asset_exists {
+            eprintln!("[nix] Warning: asset {asset_name} not found in release, skipping");
+            continue;
+        }
+
+        let source_root = archive_prefix(config, version, &archive.target).unwrap_or_else(|| ".".to_string());
+        system_hashes.push((nix_sys, asset_name, archive.sha256().to_string(), source_root));
+    }
+
+    let system_hash_refs: Vec<(&str, &str, &str, &str)> = system_hashes
+        .iter()
+        .map(|(s, a, h, r)| (*s, a.as_str(), h.as_str(), r.as_str()))
+        .collect();
+    let files = archives.first().map(|a| a.files.as_slice()).unwrap_or_default();
+
+    let flake = generate_flake(binary, &config.project.binaries(), repo, version, &system_hash_refs, files);
+
+    // Generate flake.lock
+    let tmp_dir = std::env::temp_dir().join(format!("releasor2000-nix-{version}"));
+    std::fs::create_dir_all(&tmp_dir)?;
+    std::fs::write(tmp_dir.join("flake.nix"), &flake)?;
+    let lock_cmd = format!("cd '{}' && nix flake lock", tmp_dir.display());
+    run_cmd("nix", None, "sh", &["-lc", &lock_cmd])?;
+    let flake_lock = std::fs::read_to_string(tmp_dir.join("flake.lock"))
+        .context("[nix] failed to read generated flake.lock")?;
+    std::fs::remove_dir_all(&tmp_dir).ok();
+
+    let files = [
+        ("flake.nix", Some(flake.as_str()), format!("Update {binary} to {version}")),
+        ("flake.lock", Some(flake_lock.as_str()), format!("Update flake.lock for {binary} {version}")),
+    ];
+    update_repo_files("nix", config, flake_repo, &ch.update, binary, version, &files)?;
+    println!("[nix] Updated flake.nix and flake.lock in {flake_repo}");
+    Ok(())
+}
+
+fn plan_nix(config: &Config, version: &str, archives: &[Artifact]) -> Result<Plan> {
+    let ch = config.channels.nix.as_ref().unwrap();
+    let binary = config.project.binary();
+    let repo = &config.project.repo;
+    let flake_repo = ch.flake_repo.as_deref().unwrap_or(repo);
+
+    let mut system_hashes = Vec::new();
+    for archive in archives {
+        if let Some(nix_sys) = nix_system(&archive.target) {
+            let source_root = archive_prefix(config, version, &archive.target).unwrap_or_else(|| ".".to_string());
+            system_hashes.push((nix_sys, archive.name(), archive.sha256().to_string(), source_root));
+        }
+    }
+    let system_hash_refs: Vec<(&str, &str, &str, &str)> = system_hashes
+        .iter()
+        .map(|(s, a, h, r)| (*s, a.as_str(), h.as_str(), r.as_str()))
+        .collect();
+    let files = archives.first().map(|a| a.files.as_slice()).unwrap_or_default();
+    let flake = generate_flake(binary, &config.project.binaries(), repo, version, &system_hash_refs, files);
+
+    let mut plan = Plan::new("nix");
+    plan.command("nix", &["flake", "lock"]);
+    let files = [
+        ("flake.nix", Some(flake.as_str()), format!("Update {binary} to {version}")),
+        // the lock is only generated at release time
+        ("flake.lock", None, format!("Update flake.lock for {binary} {version}")),
+    ];
+    plan_repo_files(&mut plan, config, flake_repo, &ch.update, binary, version, &files);
+    plan.file("flake.nix", flake);
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- nix_system tests ---
+
+    #[test]
+    fn nix_system_x86_64_linux() {
+        assert_eq!(nix_system("x86_64-unknown-linux-gnu"), Some("x86_64-linux"));
+    }
+
+    #[test]
+    fn nix_system_aarch64_linux() {
+        assert_eq!(nix_system("aarch64-unknown-linux-gnu"), Some("aarch64-linux"));
+    }
+
+    #[test]
+    fn nix_system_x86_64_darwin() {
+        assert_eq!(nix_system("x86_64-apple-darwin"), Some("x86_64-darwin"));
+    }
+
+    #[test]
+    fn nix_system_aarch64_darwin() {
+        assert_eq!(nix_system("aarch64-apple-darwin"), Some("aarch64-darwin"));
+    }
+
+    #[test]
+    fn nix_system_windows_target() {
+        assert_eq!(nix_system("x86_64-pc-windows-msvc"), None);
+    }
+
+    #[test]
+    fn nix_system_unknown_target() {
+        assert_eq!(nix_system("wasm32-unknown-unknown"), None);
+    }
+
+    // --- generate_flake tests ---
+
+    #[test]
+    fn generate_flake_contains_description() {
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc123", "."),
+        ], &[]);
+        assert!(flake.contains(r#"description = "mytool""#));
+    }
+
+    #[test]
+    fn generate_flake_contains_version() {
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "2.3.4", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc123", "."),
+        ], &[]);
+        assert!(flake.contains(r#"version = "2.3.4""#));
+    }
+
+    #[test]
+    fn generate_flake_contains_sha256_values() {
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "deadbeef", "."),
+            ("aarch64-darwin", "mytool-1.0.0-aarch64-apple-darwin.tar.gz", "cafebabe", "."),
+        ], &[]);
+        assert!(flake.contains(r#""deadbeef""#));
+        assert!(flake.contains(r#""cafebabe""#));
+    }
+
+    #[test]
+    fn generate_flake_contains_binary_name() {
+        let flake = generate_flake("mytool", &["mybinary"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc", "."),
+        ], &[]);
+        assert!(flake.contains(r#"pname = "mybinary""#));
+        assert!(flake.contains("install -m755 -D mybinary $out/bin/mybinary"));
+    }
+
+    #[test]
+    fn generate_flake_contains_download_urls() {
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc", "."),
+            ("aarch64-darwin", "mytool-1.0.0-aarch64-apple-darwin.tar.gz", "def", "."),
+        ], &[]);
+        assert!(flake.contains("https://github.com/owner/repo/releases/download/v1.0.0/mytool-1.0.0-"));
+    }
+
+    #[test]
+    fn generate_flake_contains_system_entries() {
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc", "."),
+            ("aarch64-darwin", "mytool-1.0.0-aarch64-apple-darwin.tar.gz", "def", "."),
+        ], &[]);
+        assert!(flake.contains(r#""x86_64-linux" = let"#));
+        assert!(flake.contains(r#""aarch64-darwin" = let"#));
+    }
+
+    #[test]
+    fn generate_flake_adds_unpackers_for_zip_and_zstd() {
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.zst", "abc", "."),
+            ("aarch64-darwin", "mytool-1.0.0-aarch64-apple-darwin.zip", "def", "."),
+        ], &[]);
+        assert!(flake.contains("/v1.0.0/mytool-1.0.0-x86_64-unknown-linux-gnu.tar.zst\";"));
+        assert!(flake.contains("nativeBuildInputs = [ pkgs.zstd ];"));
+        assert!(flake.contains("nativeBuildInputs = [ pkgs.unzip ];"));
+
+        let plain = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.xz", "abc", "."),
+        ], &[]);
+        assert!(!plain.contains("nativeBuildInputs"));
+    }
+
+    #[test]
+    fn generate_flake_installs_extras_from_prefix() {
+        let files = vec!["completions/_mytool".to_string(), "man/mytool.1".to_string(), "LICENSE".to_string()];
+        let flake = generate_flake("mytool", &["mytool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc", "mytool-1.0.0-x86_64-unknown-linux-gnu"),
+        ], &files);
+        assert!(flake.contains(r#"sourceRoot = "mytool-1.0.0-x86_64-unknown-linux-gnu";"#));
+        assert!(flake.contains("nativeBuildInputs = [ pkgs.installShellFiles ];"));
+        assert!(flake.contains(
+            "install -m755 -D mytool $out/bin/mytool\n            installShellCompletion --cmd mytool --zsh completions/_mytool\n            installManPage man/mytool.1\n"
+        ));
+    }
+
+    #[test]
+    fn generate_flake_installs_every_binary() {
+        let flake = generate_flake("mytool", &["mytool", "mytool-daemon"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "mytool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc", "."),
+        ], &[]);
+        assert!(flake.contains(r#"pname = "mytool""#));
+        assert!(flake.contains(
+            "install -m755 -D mytool $out/bin/mytool\n            install -m755 -D mytool-daemon $out/bin/mytool-daemon\n"
+        ));
+    }
+
+    #[test]
+    fn generate_flake_keeps_asset_names_verbatim() {
+        let flake = generate_flake("REPO-tool", &["REPO-tool"], "owner/repo", "1.0.0", &[
+            ("x86_64-linux", "REPO-tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "abc", "."),
+        ], &[]);
+        assert!(flake.contains("/v1.0.0/REPO-tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz\";"));
+        assert!(flake.contains(r#"pname = "REPO-tool";"#));
+        assert!(flake.contains("in { REPO-tool = pkg; default = pkg; };"));
+        assert!(flake.contains(r#"description = "REPO-tool";"#));
+    }
+}
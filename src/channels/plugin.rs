@@ -0,0 +1,228 @@
+//! External channels: an executable that receives the release as JSON on stdin and
+//! answers with a JSON result on stdout. See the README for the protocol.
+
+use anyhow::{Context as _, Result, bail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::{Channel, Context, Journal, Plan, Requirement};
+use crate::config::{Config, PluginChannel};
+
+const PROTOCOL_VERSION: u32 = 1;
+
+/// A channel configured under `[channels.plugin.<name>]`.
+pub struct Plugin {
+    name: String,
+    command: String,
+    args: Vec<String>,
+    depends_on: Vec<String>,
+}
+
+impl Plugin {
+    pub fn new(name: &str, config: &PluginChannel) -> Self {
+        Self {
+            name: name.to_string(),
+            command: config
+                .command
+                .clone()
+                .unwrap_or_else(|| format!("releasor2000-channel-{name}")),
+            args: config.args.clone(),
+            depends_on: config.depends_on.clone(),
+        }
+    }
+}
+
+impl Channel for Plugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        self.depends_on.iter().map(|d| d.as_str()).collect()
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.plugin.get(&self.name).is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![Requirement::Command(self.command.clone())]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        let request = plugin_request(ctx, &self.name, true)?;
+        let mut plan = Plan::new(&self.name);
+        plan.file("request.json", serde_json::to_string_pretty(&request)?);
+        let args: Vec<&str> = self.args.iter().map(|a| a.as_str()).collect();
+        plan.command(&self.command, &args);
+        Ok(plan)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        let name = &self.name;
+        let request = plugin_request(ctx, name, false)?;
+        let response = run_plugin(name, &self.command, &self.args, &request)?;
+        if !response.ok {
+            bail!(
+                "[{name}] plugin reported failure: {}",
+                response.message.as_deref().unwrap_or("no message")
+            );
+        }
+        for (key, value) in &response.outputs {
+            match value.as_str() {
+                Some(s) => println!("[{name}] {key}: {s}"),
+                None => println!("[{name}] {key}: {value}"),
+            }
+        }
+        println!(
+            "[{name}] {}",
+            response.message.as_deref().unwrap_or("Plugin completed")
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PluginResponse {
+    ok: bool,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    outputs: BTreeMap<String, serde_json::Value>,
+}
+
+fn plugin_request(ctx: &Context, name: &str, dry_run: bool) -> Result<serde_json::Value> {
+    let config = ctx.config;
+    let repo = &config.project.repo;
+    let version = ctx.version;
+    let options = config
+        .channels
+        .plugin
+        .get(name)
+        .map(|ch| serde_json::to_value(&ch.options))
+        .transpose()?
+        .unwrap_or_default();
+
+    let mut archives = Vec::new();
+    for archive in ctx.archives {
+        // target, path and every computed digest
+        let mut entry = serde_json::to_value(archive)?;
+        let name = archive.name();
+        entry["url"] = format!("https://github.com/{repo}/releases/download/v{version}/{name}").into();
+        entry["name"] = name.into();
+        archives.push(entry);
+    }
+    let mut packages = Vec::new();
+    for package in ctx.packages {
+        let mut entry = serde_json::to_value(package)?;
+        let name = package.name();
+        entry["url"] = format!("https://github.com/{repo}/releases/download/v{version}/{name}").into();
+        entry["name"] = name.into();
+        packages.push(entry);
+    }
+
+    Ok(serde_json::json!({
+        "protocol": PROTOCOL_VERSION,
+        "channel": name,
+        "dry_run": dry_run,
+        "project": {
+            "name": config.project.name,
+            "binary": config.project.binary(),
+            "binaries": config.project.binaries(),
+            "repo": repo,
+        },
+        "version": version,
+        "tag": format!("v{version}"),
+        "release_url": format!("https://github.com/{repo}/releases/tag/v{version}"),
+        "archives": archives,
+        "packages": packages,
+        "config": options,
+    }))
+}
+
+fn parse_response(name: &str, stdout: &str) -> Result<PluginResponse> {
+    serde_json::from_str(stdout.trim())
+        .with_context(|| format!("[{name}] plugin did not print a JSON response on stdout: {}", stdout.trim()))
+}
+
+fn run_plugin(name: &str, command: &str, args: &[String], request: &serde_json::Value) -> Result<PluginResponse> {
+    println!("[{name}] Running: {command} {}", args.join(" "));
+    let mut child = Command::new(command)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("[{name}] failed to run {command}"))?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    stdin
+        .write_all(request.to_string().as_bytes())
+        .with_context(|| format!("[{name}] failed to send request to {command}"))?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("[{name}] failed to wait for {command}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let detail = parse_response(name, &stdout)
+            .ok()
+            .and_then(|r| r.message)
+            .unwrap_or_else(|| stdout.trim().to_string());
+        bail!("[{name}] {command} failed ({}): {detail}", output.status);
+    }
+    parse_response(name, &stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_response_with_outputs() {
+        let response = parse_response(
+            "foo",
+            r#"{"ok": true, "message": "Published", "outputs": {"url": "https://example.com"}}"#,
+        )
+        .unwrap();
+        assert!(response.ok);
+        assert_eq!(response.message.as_deref(), Some("Published"));
+        assert_eq!(response.outputs["url"], "https://example.com");
+    }
+
+    #[test]
+    fn parse_response_minimal() {
+        let response = parse_response("foo", "{\"ok\": false}\n").unwrap();
+        assert!(!response.ok);
+        assert!(response.outputs.is_empty());
+    }
+
+    #[test]
+    fn parse_response_rejects_non_json() {
+        let err = parse_response("foo", "hello").unwrap_err();
+        assert!(err.to_string().contains("did not print a JSON response"), "got: {err}");
+    }
+
+    #[test]
+    fn run_plugin_round_trips_json() {
+        let request = serde_json::json!({ "version": "1.0.0" });
+        let args = vec![
+            "-c".to_string(),
+            r#"read -r input; echo "{\"ok\": true, \"outputs\": {\"got\": $input}}""#.to_string(),
+        ];
+        let response = run_plugin("foo", "sh", &args, &request).unwrap();
+        assert!(response.ok);
+        assert_eq!(response.outputs["got"]["version"], "1.0.0");
+    }
+
+    #[test]
+    fn run_plugin_reports_exit_failure() {
+        let request = serde_json::json!({});
+        let args = vec!["-c".to_string(), r#"echo '{"ok": false, "message": "boom"}'; exit 3"#.to_string()];
+        let err = run_plugin("foo", "sh", &args, &request).unwrap_err();
+        assert!(err.to_string().contains("boom"), "got: {err}");
+    }
+}
@@ -0,0 +1,327 @@
+use anyhow::{Context as _, Result, bail};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, archive_prefix, contents_request_body, github_token_requirement,
+    is_windows, put_repo_file,
+};
+use crate::archive::archive_name;
+use crate::artifact::Artifact;
+use crate::config::{ArchiveFormat, ChecksumFormat, Config};
+use crate::github;
+
+/// Pushes a Scoop manifest for the Windows archives to a bucket repository.
+pub struct Scoop;
+
+impl Channel for Scoop {
+    fn name(&self) -> &str {
+        "scoop"
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["github"]
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.scoop.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![github_token_requirement()]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_scoop(ctx.config, ctx.version, ctx.archives)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_scoop(ctx.config, ctx.version, ctx.archives)
+    }
+}
+
+fn scoop_arch(target: &str) -> Option<&'static str> {
+    if !is_windows(target) {
+        None
+    } else if target.starts_with("x86_64") {
+        Some("64bit")
+    } else if target.starts_with("aarch64") {
+        Some("arm64")
+    } else if target.starts_with("i686") || target.starts_with("i586") {
+        Some("32bit")
+    } else {
+        None
+    }
+}
+
+/// Field order follows the manifests in the main Scoop buckets.
+#[derive(Serialize)]
+struct Manifest<'a> {
+    version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    homepage: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<&'a str>,
+    architecture: BTreeMap<&'a str, Download>,
+    bin: Vec<String>,
+    checkver: &'a str,
+    autoupdate: Autoupdate<'a>,
+}
+
+#[derive(Serialize)]
+struct Download {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extract_dir: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Autoupdate<'a> {
+    architecture: BTreeMap<&'a str, Download>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<HashUrl>,
+}
+
+#[derive(Serialize)]
+struct HashUrl {
+    url: String,
+}
+
+/// A Windows archive as seen by both the release (`version`) and `autoupdate` (`$version`).
+struct ScoopArchive<'a> {
+    arch: &'static str,
+    target: &'a str,
+    format: ArchiveFormat,
+    sha256: &'a str,
+    extract_dir: Option<String>,
+    autoupdate_extract_dir: Option<String>,
+}
+
+fn generate_manifest(
+    binaries: &[&str],
+    repo: &str,
+    version: &str,
+    description: Option<&str>,
+    license: Option<&str>,
+    archives: &[ScoopArchive],
+    // checksum manifest `autoupdate` reads the new hashes from
+    checksum_file: Option<&str>,
+) -> String {
+    let binary = binaries.first().copied().unwrap_or_default();
+    let download_url = |version: &str, archive: &ScoopArchive| {
+        let asset = archive_name(binary, version, archive.target, archive.format);
+        format!("https://github.com/{repo}/releases/download/v{version}/{asset}")
+    };
+
+    let manifest = Manifest {
+        version,
+        description,
+        homepage: format!("https://github.com/{repo}"),
+        license,
+        architecture: archives
+            .iter()
+            .map(|a| {
+                let download = Download {
+                    url: download_url(version, a),
+                    hash: Some(a.sha256.to_string()),
+                    extract_dir: a.extract_dir.clone(),
+                };
+                (a.arch, download)
+            })
+            .collect(),
+        bin: binaries.iter().map(|b| format!("{b}.exe")).collect(),
+        checkver: "github",
+        autoupdate: Autoupdate {
+            architecture: archives
+                .iter()
+                .map(|a| {
+                    let download = Download {
+                        url: download_url("$version", a),
+                        hash: None,
+                        extract_dir: a.autoupdate_extract_dir.clone(),
+                    };
+                    (a.arch, download)
+                })
+                .collect(),
+            hash: checksum_file.map(|file| HashUrl { url: format!("$baseurl/{file}") }),
+        },
+    };
+
+    // Scoop buckets indent with four spaces
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(&mut out, formatter);
+    manifest.serialize(&mut ser).expect("manifest serializes");
+    let mut json = String::from_utf8(out).expect("serde_json writes UTF-8");
+    json.push('\n');
+    json
+}
+
+fn render_manifest(config: &Config, version: &str, archives: &[Artifact]) -> Result<String> {
+    let ch = config.channels.scoop.as_ref().unwrap();
+
+    // the first archive per architecture wins, in the configured target order
+    let mut windows: Vec<ScoopArchive> = Vec::new();
+    for archive in archives {
+        let Some(arch) = scoop_arch(&archive.target) else { continue };
+        if windows.iter().any(|w| w.arch == arch) {
+            continue;
+        }
+        windows.push(ScoopArchive {
+            arch,
+            target: &archive.target,
+            format: archive.format,
+            sha256: archive.sha256(),
+            extract_dir: archive_prefix(config, version, &archive.target),
+            autoupdate_extract_dir: archive_prefix(config, "$version", &archive.target),
+        });
+    }
+    if windows.is_empty() {
+        bail!("[scoop] no Windows targets in build.targets");
+    }
+
+    // Scoop can only read a hash for `$basename` out of a `<hash>  <file>` manifest
+    let checksum_file = config
+        .channels
+        .github
+        .as_ref()
+        .filter(|gh| gh.checksums && gh.checksum_format == ChecksumFormat::Gnu)
+        .map(|gh| gh.checksum_file.as_str());
+
+    Ok(generate_manifest(
+        &config.project.binaries(),
+        &config.project.repo,
+        version,
+        ch.description.as_deref(),
+        ch.license.as_deref(),
+        &windows,
+        checksum_file,
+    ))
+}
+
+fn release_scoop(config: &Config, version: &str, archives: &[Artifact]) -> Result<()> {
+    let ch = config.channels.scoop.as_ref().unwrap();
+    let manifest_name = ch.manifest_name.as_deref().unwrap_or(&config.project.name);
+    let repo = &config.project.repo;
+
+    let release_url = format!("https://api.github.com/repos/{repo}/releases/tags/v{version}");
+    github::api("scoop", "GET", &release_url, None)
+        .with_context(|| format!("[scoop] GitHub release v{version} not found — run the github channel first"))?;
+
+    let manifest = render_manifest(config, version, archives)?;
+
+    let file_path = format!("bucket/{manifest_name}.json");
+    put_repo_file("scoop", &ch.bucket, None, &file_path, &manifest, &format!("Update {manifest_name} to {version}"))?;
+    println!("[scoop] Updated manifest {manifest_name} in {}", ch.bucket);
+    Ok(())
+}
+
+fn plan_scoop(config: &Config, version: &str, archives: &[Artifact]) -> Result<Plan> {
+    let ch = config.channels.scoop.as_ref().unwrap();
+    let manifest_name = ch.manifest_name.as_deref().unwrap_or(&config.project.name);
+    let manifest = render_manifest(config, version, archives)?;
+    let file_path = format!("bucket/{manifest_name}.json");
+
+    let mut plan = Plan::new("scoop");
+    let body = contents_request_body(&format!("Update {manifest_name} to {version}"), &manifest, None, None);
+    plan.file(&file_path, manifest);
+    plan.request(
+        "PUT",
+        format!("https://api.github.com/repos/{}/contents/{file_path}", ch.bucket),
+        Some(body),
+    );
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x64(extract_dir: Option<&str>) -> ScoopArchive<'static> {
+        ScoopArchive {
+            arch: "64bit",
+            target: "x86_64-pc-windows-msvc",
+            format: ArchiveFormat::Zip,
+            sha256: "aaa",
+            extract_dir: extract_dir.map(|d| d.replace("$version", "1.2.3")),
+            autoupdate_extract_dir: extract_dir.map(str::to_string),
+        }
+    }
+
+    // --- scoop_arch tests ---
+
+    #[test]
+    fn scoop_arch_maps_windows_targets() {
+        assert_eq!(scoop_arch("x86_64-pc-windows-msvc"), Some("64bit"));
+        assert_eq!(scoop_arch("aarch64-pc-windows-msvc"), Some("arm64"));
+        assert_eq!(scoop_arch("i686-pc-windows-gnu"), Some("32bit"));
+        assert_eq!(scoop_arch("x86_64-unknown-linux-gnu"), None);
+    }
+
+    // --- generate_manifest tests ---
+
+    #[test]
+    fn generate_manifest_lists_release_and_autoupdate_urls() {
+        let arm = ScoopArchive {
+            arch: "arm64",
+            target: "aarch64-pc-windows-msvc",
+            format: ArchiveFormat::Zip,
+            sha256: "bbb",
+            extract_dir: None,
+            autoupdate_extract_dir: None,
+        };
+        let json = generate_manifest(
+            &["tool", "toold"],
+            "owner/repo",
+            "1.2.3",
+            Some("A tool"),
+            Some("MIT"),
+            &[x64(None), arm],
+            Some("SHA256SUMS"),
+        );
+        let manifest: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(manifest["version"], "1.2.3");
+        assert_eq!(manifest["description"], "A tool");
+        assert_eq!(manifest["homepage"], "https://github.com/owner/repo");
+        assert_eq!(manifest["license"], "MIT");
+        assert_eq!(
+            manifest["architecture"]["64bit"]["url"],
+            "https://github.com/owner/repo/releases/download/v1.2.3/tool-1.2.3-x86_64-pc-windows-msvc.zip"
+        );
+        assert_eq!(manifest["architecture"]["64bit"]["hash"], "aaa");
+        assert_eq!(manifest["architecture"]["arm64"]["hash"], "bbb");
+        assert_eq!(manifest["bin"], serde_json::json!(["tool.exe", "toold.exe"]));
+        assert_eq!(manifest["checkver"], "github");
+        assert_eq!(
+            manifest["autoupdate"]["architecture"]["64bit"]["url"],
+            "https://github.com/owner/repo/releases/download/v$version/tool-$version-x86_64-pc-windows-msvc.zip"
+        );
+        assert!(manifest["autoupdate"]["architecture"]["64bit"].get("hash").is_none());
+        assert_eq!(manifest["autoupdate"]["hash"]["url"], "$baseurl/SHA256SUMS");
+    }
+
+    #[test]
+    fn generate_manifest_keeps_field_order_and_indent() {
+        let json = generate_manifest(&["tool"], "owner/repo", "1.2.3", None, None, &[x64(None)], None);
+        assert!(json.starts_with("{\n    \"version\": \"1.2.3\",\n    \"homepage\""));
+        let keys: Vec<usize> = ["\"architecture\"", "\"bin\"", "\"checkver\"", "\"autoupdate\""]
+            .iter()
+            .map(|k| json.find(k).unwrap())
+            .collect();
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+        assert!(!json.contains("\"license\""));
+        assert!(!json.contains("$baseurl"));
+    }
+
+    #[test]
+    fn generate_manifest_sets_extract_dir_for_archive_prefix() {
+        let json =
+            generate_manifest(&["tool"], "owner/repo", "1.2.3", None, None, &[x64(Some("tool-$version"))], None);
+        let manifest: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(manifest["architecture"]["64bit"]["extract_dir"], "tool-1.2.3");
+        assert_eq!(manifest["autoupdate"]["architecture"]["64bit"]["extract_dir"], "tool-$version");
+    }
+}
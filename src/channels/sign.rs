@@ -0,0 +1,186 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{Plan, command_exists, run_cmd};
+use crate::config::{Signing, SigningMethod};
+
+pub(super) const SIGNATURE_CONTENT_TYPE: &str = "text/plain";
+
+/// `<file>.minisig`, `<file>.sig` or `<file>.asc`, next to `file`.
+pub(super) fn signature_path(signing: &Signing, file: &Path) -> PathBuf {
+    let mut path = file.as_os_str().to_owned();
+    path.push(match signing.method {
+        SigningMethod::Minisign => ".minisig",
+        SigningMethod::Ssh => ".sig",
+        SigningMethod::Gpg => ".asc",
+    });
+    PathBuf::from(path)
+}
+
+fn program(method: SigningMethod) -> &'static str {
+    match method {
+        SigningMethod::Minisign => "minisign",
+        SigningMethod::Ssh => "ssh-keygen",
+        SigningMethod::Gpg => "gpg",
+    }
+}
+
+/// Program and arguments that write the signature of `file`.
+fn sign_command(signing: &Signing, file: &Path) -> (&'static str, Vec<String>) {
+    let signature = signature_path(signing, file).display().to_string();
+    let file = file.display().to_string();
+    let key = signing.key.clone();
+    let args: Vec<String> = match signing.method {
+        SigningMethod::Minisign => vec!["-S".into(), "-s".into(), key, "-m".into(), file, "-x".into(), signature],
+        SigningMethod::Ssh => vec!["-Y".into(), "sign".into(), "-f".into(), key, "-n".into(), signing.namespace.clone(), file],
+        SigningMethod::Gpg => {
+            let args = ["--batch", "--yes", "--local-user", &key, "--armor", "--detach-sign", "--output", &signature, &file];
+            args.map(String::from).to_vec()
+        }
+    };
+    (program(signing.method), args)
+}
+
+/// What stops `signing` from working on this machine: a missing program, or a key that isn't
+/// there. Checked before anything is built.
+pub(super) fn signing_problems(signing: &Signing) -> Vec<String> {
+    let program = program(signing.method);
+    if !command_exists(program) {
+        return vec![format!("{program} command is required for: signing")];
+    }
+    let key = &signing.key;
+    match signing.method {
+        SigningMethod::Gpg => {
+            if !gpg_secret_key_exists(key) {
+                return vec![format!("GPG secret key {key} is not in the keyring")];
+            }
+        }
+        SigningMethod::Minisign | SigningMethod::Ssh => {
+            if !Path::new(key).is_file() {
+                return vec![format!("signing key {key} not found")];
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Whether gpg has the secret key `key` (a key ID, fingerprint or user ID) in its keyring.
+pub(super) fn gpg_secret_key_exists(key: &str) -> bool {
+    let listed = Command::new("gpg").args(["--batch", "--list-secret-keys", "--", key]).output();
+    listed.is_ok_and(|o| o.status.success())
+}
+
+/// Signs `file` and returns the path of its signature.
+pub(super) fn sign_file(signing: &Signing, file: &Path) -> Result<PathBuf> {
+    let signature = signature_path(signing, file);
+    // ssh-keygen asks before overwriting a signature left by an earlier attempt
+    std::fs::remove_file(&signature).ok();
+    let (program, args) = sign_command(signing, file);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_cmd("sign", None, program, &args)?;
+    Ok(signature)
+}
+
+/// Adds signing `file` to `plan` and returns the path its signature would have.
+pub(super) fn plan_signature(plan: &mut Plan, signing: &Signing, file: &Path) -> PathBuf {
+    let (program, args) = sign_command(signing, file);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    plan.command(program, &args);
+    signature_path(signing, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing(method: SigningMethod) -> Signing {
+        Signing {
+            method,
+            key: "release.key".to_string(),
+            public_key: None,
+            namespace: "file".to_string(),
+        }
+    }
+
+    // --- sign_command tests ---
+
+    #[test]
+    fn minisign_writes_minisig_next_to_the_file() {
+        let signing = signing(SigningMethod::Minisign);
+        let file = Path::new("target/release-staging/tool.tar.gz");
+        assert_eq!(signature_path(&signing, file), Path::new("target/release-staging/tool.tar.gz.minisig"));
+        let (program, args) = sign_command(&signing, file);
+        assert_eq!(program, "minisign");
+        assert_eq!(
+            args.join(" "),
+            "-S -s release.key -m target/release-staging/tool.tar.gz -x target/release-staging/tool.tar.gz.minisig"
+        );
+    }
+
+    #[test]
+    fn ssh_signs_in_the_namespace() {
+        let signing = signing(SigningMethod::Ssh);
+        let file = Path::new("SHA256SUMS");
+        assert_eq!(signature_path(&signing, file), Path::new("SHA256SUMS.sig"));
+        let (program, args) = sign_command(&signing, file);
+        assert_eq!(program, "ssh-keygen");
+        assert_eq!(args.join(" "), "-Y sign -f release.key -n file SHA256SUMS");
+    }
+
+    #[test]
+    fn gpg_writes_armored_detached_signature() {
+        let mut signing = signing(SigningMethod::Gpg);
+        signing.key = "ABCD1234".to_string();
+        let file = Path::new("SHA256SUMS");
+        assert_eq!(signature_path(&signing, file), Path::new("SHA256SUMS.asc"));
+        let (program, args) = sign_command(&signing, file);
+        assert_eq!(program, "gpg");
+        assert_eq!(
+            args.join(" "),
+            "--batch --yes --local-user ABCD1234 --armor --detach-sign --output SHA256SUMS.asc SHA256SUMS"
+        );
+    }
+
+    // --- signing_problems tests ---
+
+    #[test]
+    fn signing_problems_reports_a_missing_key() {
+        let mut signing = signing(SigningMethod::Ssh);
+        signing.key = "/nonexistent/releasor2000.key".to_string();
+        if command_exists("ssh-keygen") {
+            assert_eq!(signing_problems(&signing), vec!["signing key /nonexistent/releasor2000.key not found"]);
+        }
+        signing.method = SigningMethod::Gpg;
+        signing.key = "releasor2000-no-such-key@example.invalid".to_string();
+        if command_exists("gpg") {
+            assert_eq!(
+                signing_problems(&signing),
+                vec!["GPG secret key releasor2000-no-such-key@example.invalid is not in the keyring"]
+            );
+        }
+    }
+
+    #[test]
+    fn sign_file_replaces_an_earlier_ssh_signature() {
+        let dir = std::env::temp_dir().join(format!("releasor2000-sign-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = dir.join("key");
+        let keygen = std::process::Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key)
+            .status();
+        if !keygen.is_ok_and(|s| s.success()) {
+            return; // no ssh-keygen on this machine
+        }
+        let file = dir.join("SHA256SUMS");
+        std::fs::write(&file, "abc  tool.tar.gz\n").unwrap();
+        let mut signing = signing(SigningMethod::Ssh);
+        signing.key = key.display().to_string();
+
+        std::fs::write(signature_path(&signing, &file), "stale").unwrap();
+        let signature = sign_file(&signing, &file).unwrap();
+        assert!(std::fs::read_to_string(&signature).unwrap().starts_with("-----BEGIN SSH SIGNATURE-----"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,379 @@
+use anyhow::{Context as _, Result, bail};
+use std::path::{Path, PathBuf};
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, archive_prefix, contents_request_body, github_token_requirement,
+    is_windows, put_repo_file, staging_dir,
+};
+use crate::artifact::Artifact;
+use crate::config::{ArchiveFormat, Config};
+use crate::github;
+
+const MANIFEST_VERSION: &str = "1.6.0";
+const UPSTREAM: &str = "microsoft/winget-pkgs";
+const UPSTREAM_BRANCH: &str = "master";
+
+/// Writes the winget manifests for the Windows zip archives and optionally pushes them to a
+/// `winget-pkgs` fork, ready for a pull request.
+pub struct Winget;
+
+impl Channel for Winget {
+    fn name(&self) -> &str {
+        "winget"
+    }
+
+    fn depends_on(&self) -> Vec<&str> {
+        vec!["github"]
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.winget.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, _config: &Config) -> Vec<Requirement> {
+        vec![github_token_requirement()]
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_winget(ctx.config, ctx.version, ctx.archives)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_winget(ctx.config, ctx.version, ctx.archives)
+    }
+}
+
+fn winget_arch(target: &str) -> Option<&'static str> {
+    if !is_windows(target) {
+        None
+    } else if target.starts_with("x86_64") {
+        Some("x64")
+    } else if target.starts_with("aarch64") {
+        Some("arm64")
+    } else if target.starts_with("i686") || target.starts_with("i586") {
+        Some("x86")
+    } else {
+        None
+    }
+}
+
+/// Quotes a YAML scalar when it would otherwise be read as something other than a plain string.
+fn yaml_str(value: &str) -> String {
+    let special_start = value.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c) || c.is_whitespace());
+    let ambiguous = matches!(value.to_ascii_lowercase().as_str(), "true" | "false" | "yes" | "no" | "null" | "~")
+        || value.parse::<f64>().is_ok();
+    if value.is_empty()
+        || special_start
+        || ambiguous
+        || value.ends_with(char::is_whitespace)
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.contains('\n')
+    {
+        format!("'{}'", value.replace('\'', "''"))
+    } else {
+        value.to_string()
+    }
+}
+
+fn header(manifest_type: &str) -> String {
+    format!("# yaml-language-server: $schema=https://aka.ms/winget-manifest.{manifest_type}.{MANIFEST_VERSION}.schema.json\n\n")
+}
+
+/// Directory of the manifests inside `winget-pkgs`, e.g. `manifests/a/Acme/Tool/1.2.3`.
+fn manifest_dir(identifier: &str, version: &str) -> String {
+    let first = identifier.chars().next().unwrap_or_default().to_ascii_lowercase();
+    format!("manifests/{first}/{}/{version}", identifier.replace('.', "/"))
+}
+
+fn generate_version_manifest(identifier: &str, version: &str) -> String {
+    format!(
+        "{}PackageIdentifier: {}\nPackageVersion: {}\nDefaultLocale: en-US\nManifestType: version\nManifestVersion: {MANIFEST_VERSION}\n",
+        header("version"),
+        yaml_str(identifier),
+        yaml_str(version),
+    )
+}
+
+struct LocaleInfo<'a> {
+    publisher: &'a str,
+    package_name: &'a str,
+    license: &'a str,
+    short_description: &'a str,
+}
+
+fn generate_locale_manifest(identifier: &str, version: &str, repo: &str, info: &LocaleInfo) -> String {
+    let fields = [
+        ("PackageIdentifier", yaml_str(identifier)),
+        ("PackageVersion", yaml_str(version)),
+        ("PackageLocale", "en-US".to_string()),
+        ("Publisher", yaml_str(info.publisher)),
+        ("PackageName", yaml_str(info.package_name)),
+        ("PackageUrl", format!("https://github.com/{repo}")),
+        ("License", yaml_str(info.license)),
+        ("ShortDescription", yaml_str(info.short_description)),
+        ("ReleaseNotesUrl", format!("https://github.com/{repo}/releases/tag/v{version}")),
+        ("ManifestType", "defaultLocale".to_string()),
+        ("ManifestVersion", MANIFEST_VERSION.to_string()),
+    ];
+    let body: String = fields.iter().map(|(key, value)| format!("{key}: {value}\n")).collect();
+    format!("{}{body}", header("defaultLocale"))
+}
+
+/// The first of `binaries` names the release assets.
+fn generate_installer_manifest(
+    identifier: &str,
+    binaries: &[&str],
+    repo: &str,
+    version: &str,
+    // (winget architecture, asset name, sha256, directory the archive unpacks into)
+    installers: &[(&str, &str, &str, Option<&str>)],
+) -> String {
+    let mut out = header("installer");
+    out.push_str(&format!(
+        "PackageIdentifier: {}\nPackageVersion: {}\nInstallerType: zip\nNestedInstallerType: portable\nInstallers:\n",
+        yaml_str(identifier),
+        yaml_str(version),
+    ));
+    for (arch, asset, sha256, prefix) in installers {
+        out.push_str(&format!("- Architecture: {arch}\n"));
+        out.push_str(&format!("  InstallerUrl: https://github.com/{repo}/releases/download/v{version}/{asset}\n"));
+        out.push_str(&format!("  InstallerSha256: {}\n", sha256.to_ascii_uppercase()));
+        out.push_str("  NestedInstallerFiles:\n");
+        for binary in binaries {
+            let path = match prefix {
+                Some(prefix) => format!("{}\\{binary}.exe", prefix.replace('/', "\\")),
+                None => format!("{binary}.exe"),
+            };
+            out.push_str(&format!("  - RelativeFilePath: {}\n", yaml_str(&path)));
+            out.push_str(&format!("    PortableCommandAlias: {}\n", yaml_str(binary)));
+        }
+    }
+    out.push_str(&format!("ManifestType: installer\nManifestVersion: {MANIFEST_VERSION}\n"));
+    out
+}
+
+/// Renders the three manifests as (path inside `winget-pkgs`, content).
+fn render_manifests(config: &Config, version: &str, archives: &[Artifact]) -> Result<Vec<(String, String)>> {
+    let ch = config.channels.winget.as_ref().unwrap();
+    let identifier = ch.package_identifier(&config.project);
+
+    // winget only unpacks zip archives; the first per architecture wins
+    let mut installers: Vec<(&str, String, &str, Option<String>)> = Vec::new();
+    for archive in archives {
+        let Some(arch) = winget_arch(&archive.target) else { continue };
+        if archive.format != ArchiveFormat::Zip {
+            eprintln!("[winget] Warning: {} is not a zip archive, skipping", archive.name());
+            continue;
+        }
+        if installers.iter().any(|(a, ..)| *a == arch) {
+            continue;
+        }
+        let prefix = archive_prefix(config, version, &archive.target);
+        installers.push((arch, archive.name(), archive.sha256(), prefix));
+    }
+    if installers.is_empty() {
+        bail!("[winget] no Windows zip archives to publish");
+    }
+    let installer_refs: Vec<(&str, &str, &str, Option<&str>)> = installers
+        .iter()
+        .map(|(arch, asset, sha, prefix)| (*arch, asset.as_str(), *sha, prefix.as_deref()))
+        .collect();
+
+    let info = LocaleInfo {
+        publisher: &ch.publisher,
+        package_name: ch.package_name(&config.project),
+        license: &ch.license,
+        short_description: &ch.short_description,
+    };
+    let repo = &config.project.repo;
+    let dir = manifest_dir(&identifier, version);
+    Ok(vec![
+        (format!("{dir}/{identifier}.yaml"), generate_version_manifest(&identifier, version)),
+        (
+            format!("{dir}/{identifier}.installer.yaml"),
+            generate_installer_manifest(&identifier, &config.project.binaries(), repo, version, &installer_refs),
+        ),
+        (
+            format!("{dir}/{identifier}.locale.en-US.yaml"),
+            generate_locale_manifest(&identifier, version, repo, &info),
+        ),
+    ])
+}
+
+fn write_manifests(dir: &Path, manifests: &[(String, String)]) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for (path, content) in manifests {
+        let dest = dir.join(path);
+        std::fs::create_dir_all(dest.parent().unwrap())?;
+        std::fs::write(&dest, content)?;
+        written.push(dest);
+    }
+    Ok(written)
+}
+
+fn branch_name(config: &Config, version: &str) -> String {
+    let ch = config.channels.winget.as_ref().unwrap();
+    ch.branch.clone().unwrap_or_else(|| format!("{}-{version}", ch.package_identifier(&config.project)))
+}
+
+/// Brings the fork up to date with upstream and creates `branch` from it, unless it already exists.
+fn prepare_branch(fork: &str, branch: &str) -> Result<()> {
+    let sync_url = format!("https://api.github.com/repos/{fork}/merge-upstream");
+    github::api("winget", "POST", &sync_url, Some(&serde_json::json!({ "branch": UPSTREAM_BRANCH })))
+        .with_context(|| format!("[winget] failed to sync {fork} with {UPSTREAM}"))?;
+
+    let branch_url = format!("https://api.github.com/repos/{fork}/git/ref/heads/{branch}");
+    if github::get_optional("winget", &branch_url)?.is_some() {
+        return Ok(());
+    }
+    let base_url = format!("https://api.github.com/repos/{fork}/git/ref/heads/{UPSTREAM_BRANCH}");
+    let base = github::api("winget", "GET", &base_url, None)?;
+    let sha = base["object"]["sha"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("[winget] missing sha for {fork}@{UPSTREAM_BRANCH}"))?;
+    let refs_url = format!("https://api.github.com/repos/{fork}/git/refs");
+    let body = serde_json::json!({ "ref": format!("refs/heads/{branch}"), "sha": sha });
+    github::api("winget", "POST", &refs_url, Some(&body))?;
+    Ok(())
+}
+
+fn release_winget(config: &Config, version: &str, archives: &[Artifact]) -> Result<()> {
+    let ch = config.channels.winget.as_ref().unwrap();
+    let identifier = ch.package_identifier(&config.project);
+    let manifests = render_manifests(config, version, archives)?;
+    let staged = staging_dir(version).join("winget");
+    write_manifests(&staged, &manifests)?;
+
+    let Some(fork) = ch.fork.as_deref() else {
+        println!(
+            "[winget] Wrote manifests to {}; submit them to {UPSTREAM} with a pull request",
+            staged.join(manifest_dir(&identifier, version)).display()
+        );
+        return Ok(());
+    };
+
+    let branch = branch_name(config, version);
+    prepare_branch(fork, &branch)?;
+    for (path, content) in &manifests {
+        put_repo_file("winget", fork, Some(&branch), path, content, &format!("New version: {identifier} version {version}"))?;
+    }
+    let owner = fork.split('/').next().unwrap_or(fork);
+    println!("[winget] Pushed manifests to {fork}@{branch}");
+    println!("[winget] Open a pull request: https://github.com/{UPSTREAM}/compare/{UPSTREAM_BRANCH}...{owner}:{branch}");
+    Ok(())
+}
+
+fn plan_winget(config: &Config, version: &str, archives: &[Artifact]) -> Result<Plan> {
+    let ch = config.channels.winget.as_ref().unwrap();
+    let identifier = ch.package_identifier(&config.project);
+    let manifests = render_manifests(config, version, archives)?;
+
+    let mut plan = Plan::new("winget");
+    let Some(fork) = ch.fork.as_deref() else {
+        for (path, content) in manifests {
+            plan.file(&path, content);
+        }
+        return Ok(plan);
+    };
+
+    let branch = branch_name(config, version);
+    plan.request(
+        "POST",
+        format!("https://api.github.com/repos/{fork}/merge-upstream"),
+        Some(serde_json::json!({ "branch": UPSTREAM_BRANCH })),
+    );
+    plan.request(
+        "POST",
+        format!("https://api.github.com/repos/{fork}/git/refs"),
+        Some(serde_json::json!({ "ref": format!("refs/heads/{branch}"), "sha": format!("<{fork}@{UPSTREAM_BRANCH}>") })),
+    );
+    for (path, content) in manifests {
+        let message = format!("New version: {identifier} version {version}");
+        let body = contents_request_body(&message, &content, None, Some(&branch));
+        plan.file(&path, content);
+        plan.request("PUT", format!("https://api.github.com/repos/{fork}/contents/{path}"), Some(body));
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- yaml_str tests ---
+
+    #[test]
+    fn yaml_str_quotes_only_when_needed() {
+        assert_eq!(yaml_str("Acme Corp"), "Acme Corp");
+        assert_eq!(yaml_str("1.2.3"), "1.2.3");
+        assert_eq!(yaml_str("1.2"), "'1.2'");
+        assert_eq!(yaml_str("yes"), "'yes'");
+        assert_eq!(yaml_str("Fast: really"), "'Fast: really'");
+        assert_eq!(yaml_str("it's #1"), "'it''s #1'");
+        assert_eq!(yaml_str(""), "''");
+    }
+
+    // --- manifest tests ---
+
+    #[test]
+    fn manifest_dir_follows_winget_pkgs_layout() {
+        assert_eq!(manifest_dir("Acme.Tool", "1.2.3"), "manifests/a/Acme/Tool/1.2.3");
+        assert_eq!(manifest_dir("Acme.Tools.Cli", "1.2.3"), "manifests/a/Acme/Tools/Cli/1.2.3");
+    }
+
+    #[test]
+    fn generate_version_manifest_sets_default_locale() {
+        let manifest = generate_version_manifest("Acme.Tool", "1.2.3");
+        assert!(manifest.starts_with("# yaml-language-server: $schema=https://aka.ms/winget-manifest.version.1.6.0.schema.json\n"));
+        assert!(manifest.contains("PackageIdentifier: Acme.Tool\nPackageVersion: 1.2.3\nDefaultLocale: en-US\n"));
+        assert!(manifest.ends_with("ManifestType: version\nManifestVersion: 1.6.0\n"));
+    }
+
+    #[test]
+    fn generate_locale_manifest_has_required_fields() {
+        let info = LocaleInfo {
+            publisher: "Acme",
+            package_name: "Tool",
+            license: "MIT",
+            short_description: "Does things: fast",
+        };
+        let manifest = generate_locale_manifest("Acme.Tool", "1.2.3", "owner/repo", &info);
+        assert!(manifest.contains("PackageLocale: en-US\nPublisher: Acme\nPackageName: Tool\n"));
+        assert!(manifest.contains("License: MIT\n"));
+        assert!(manifest.contains("ShortDescription: 'Does things: fast'\n"));
+        assert!(manifest.contains("ReleaseNotesUrl: https://github.com/owner/repo/releases/tag/v1.2.3\n"));
+        assert!(manifest.contains("ManifestType: defaultLocale\n"));
+    }
+
+    #[test]
+    fn generate_installer_manifest_uses_uppercase_sha_and_portable_files() {
+        let manifest = generate_installer_manifest(
+            "Acme.Tool",
+            &["tool", "toold"],
+            "owner/repo",
+            "1.2.3",
+            &[
+                ("x64", "tool-1.2.3-x86_64-pc-windows-msvc.zip", "abc123", None),
+                ("arm64", "tool-1.2.3-aarch64-pc-windows-msvc.zip", "def456", Some("tool-1.2.3")),
+            ],
+        );
+        assert!(manifest.contains("InstallerType: zip\nNestedInstallerType: portable\n"));
+        assert!(manifest.contains(
+            "- Architecture: x64\n  InstallerUrl: https://github.com/owner/repo/releases/download/v1.2.3/tool-1.2.3-x86_64-pc-windows-msvc.zip\n  InstallerSha256: ABC123\n"
+        ));
+        assert!(manifest.contains("  - RelativeFilePath: tool.exe\n    PortableCommandAlias: tool\n"));
+        assert!(manifest.contains("  - RelativeFilePath: toold.exe\n    PortableCommandAlias: toold\n"));
+        assert!(manifest.contains("  InstallerSha256: DEF456\n"));
+        assert!(manifest.contains("  - RelativeFilePath: tool-1.2.3\\tool.exe\n"));
+        assert!(manifest.ends_with("ManifestType: installer\nManifestVersion: 1.6.0\n"));
+    }
+
+    #[test]
+    fn winget_arch_maps_windows_targets() {
+        assert_eq!(winget_arch("x86_64-pc-windows-msvc"), Some("x64"));
+        assert_eq!(winget_arch("aarch64-pc-windows-msvc"), Some("arm64"));
+        assert_eq!(winget_arch("i686-pc-windows-msvc"), Some("x86"));
+        assert_eq!(winget_arch("x86_64-apple-darwin"), None);
+    }
+}
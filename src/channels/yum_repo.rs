@@ -0,0 +1,338 @@
+use anyhow::{Context as _, Result};
+use sha2::{Digest as _, Sha256};
+use std::path::{Path, PathBuf};
+
+use super::{
+    Channel, Context, Journal, Plan, Requirement, copy_hosted_packages, gzip, hosted_packages, run_cmd, update_hosted_repo,
+};
+use crate::archive::source_date_epoch;
+use crate::config::Config;
+use crate::package::{Dependency, PackageFormat, RpmInfo, read_rpm};
+
+/// Maintains a yum/dnf repository of the `.rpm` packages in a directory or on a branch.
+pub struct YumRepo;
+
+impl Channel for YumRepo {
+    fn name(&self) -> &str {
+        "yum-repo"
+    }
+
+    fn enabled(&self, config: &Config) -> bool {
+        config.channels.yum_repo.as_ref().is_some_and(|ch| ch.enabled)
+    }
+
+    fn requirements(&self, config: &Config) -> Vec<Requirement> {
+        let mut requirements = vec![Requirement::Command("git".to_string())];
+        if let Some(key) = config.channels.yum_repo.as_ref().and_then(|ch| ch.gpg_key.clone()) {
+            requirements.push(Requirement::Command("gpg".to_string()));
+            requirements.push(Requirement::GpgKey(key));
+        }
+        requirements
+    }
+
+    fn plan(&self, ctx: &Context) -> Result<Plan> {
+        plan_yum_repo(ctx)
+    }
+
+    fn publish(&self, ctx: &Context, _journal: &mut Journal) -> Result<()> {
+        release_yum_repo(ctx)
+    }
+}
+
+/// This release's `.rpm` packages at their paths in the repository.
+fn new_packages(ctx: &Context) -> Vec<(String, PathBuf)> {
+    ctx.packages
+        .iter()
+        .filter(|p| p.format == PackageFormat::Rpm)
+        .map(|p| (format!("Packages/{}", p.name()), p.path.clone()))
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn version_element(info: &RpmInfo) -> String {
+    format!("<version epoch=\"{}\" ver=\"{}\" rel=\"{}\"/>", info.epoch, escape(&info.version), escape(&info.release))
+}
+
+fn dependency_entries(kind: &str, dependencies: &[Dependency]) -> String {
+    if dependencies.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("    <rpm:{kind}>\n");
+    for dep in dependencies {
+        out.push_str(&format!("      <rpm:entry name=\"{}\"", escape(&dep.name)));
+        if let Some(flags) = dep.flags {
+            // `[epoch:]version[-release]`
+            let (epoch, rest) = dep.version.split_once(':').unwrap_or(("0", &dep.version));
+            out.push_str(&format!(" flags=\"{flags}\" epoch=\"{epoch}\""));
+            match rest.rsplit_once('-') {
+                Some((ver, rel)) => out.push_str(&format!(" ver=\"{}\" rel=\"{}\"", escape(ver), escape(rel))),
+                None => out.push_str(&format!(" ver=\"{}\"", escape(rest))),
+            }
+        }
+        out.push_str("/>\n");
+    }
+    out.push_str(&format!("    </rpm:{kind}>\n"));
+    out
+}
+
+fn file_element(indent: &str, (path, is_dir): &(String, bool)) -> String {
+    let kind = if *is_dir { " type=\"dir\"" } else { "" };
+    format!("{indent}<file{kind}>{}</file>\n", escape(path))
+}
+
+/// `primary.xml` only lists the files createrepo does, which dependency resolution may ask about.
+fn is_primary_file(path: &str) -> bool {
+    path.contains("bin/") || path.starts_with("/etc/") || path == "/usr/lib/sendmail"
+}
+
+/// An indexed `.rpm`: what it says about itself, where it is and its checksum.
+struct Indexed {
+    info: RpmInfo,
+    href: String,
+    sha256: String,
+    size: u64,
+}
+
+fn primary_package(pkg: &Indexed) -> String {
+    let info = &pkg.info;
+    let mut out = String::from("<package type=\"rpm\">\n");
+    out.push_str(&format!("  <name>{}</name>\n  <arch>{}</arch>\n", escape(&info.name), escape(&info.arch)));
+    out.push_str(&format!("  {}\n", version_element(info)));
+    out.push_str(&format!("  <checksum type=\"sha256\" pkgid=\"YES\">{}</checksum>\n", pkg.sha256));
+    out.push_str(&format!("  <summary>{}</summary>\n", escape(&info.summary)));
+    out.push_str(&format!("  <description>{}</description>\n", escape(&info.description)));
+    out.push_str(&format!("  <packager>{}</packager>\n", escape(&info.packager)));
+    out.push_str(&format!("  <url>{}</url>\n", escape(&info.url)));
+    out.push_str(&format!("  <time file=\"{0}\" build=\"{0}\"/>\n", info.build_time));
+    out.push_str(&format!(
+        "  <size package=\"{}\" installed=\"{}\" archive=\"{}\"/>\n",
+        pkg.size, info.installed_size, info.archive_size
+    ));
+    out.push_str(&format!("  <location href=\"{}\"/>\n", escape(&pkg.href)));
+    out.push_str("  <format>\n");
+    out.push_str(&format!("    <rpm:license>{}</rpm:license>\n", escape(&info.license)));
+    out.push_str(&format!("    <rpm:vendor>{}</rpm:vendor>\n", escape(&info.vendor)));
+    out.push_str(&format!("    <rpm:group>{}</rpm:group>\n", escape(&info.group)));
+    out.push_str(&format!("    <rpm:buildhost>{}</rpm:buildhost>\n", escape(&info.build_host)));
+    out.push_str(&format!("    <rpm:sourcerpm>{}</rpm:sourcerpm>\n", escape(&info.source_rpm)));
+    out.push_str(&format!(
+        "    <rpm:header-range start=\"{}\" end=\"{}\"/>\n",
+        info.header_range.0, info.header_range.1
+    ));
+    out.push_str(&dependency_entries("provides", &info.provides));
+    out.push_str(&dependency_entries("requires", &info.requires));
+    for file in info.files.iter().filter(|(path, _)| is_primary_file(path)) {
+        out.push_str(&file_element("    ", file));
+    }
+    out.push_str("  </format>\n</package>\n");
+    out
+}
+
+/// Renders `primary.xml`, `filelists.xml` and `other.xml` for every package in the pool.
+fn render_metadata(pool: &[(String, PathBuf)]) -> Result<Vec<(&'static str, String)>> {
+    let mut indexed = Vec::new();
+    for (href, path) in pool {
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        indexed.push(Indexed {
+            info: read_rpm(path)?,
+            href: href.clone(),
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+            size: bytes.len() as u64,
+        });
+    }
+    let count = indexed.len();
+    let header = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+    let mut primary = format!(
+        "{header}<metadata xmlns=\"http://linux.duke.edu/metadata/common\" xmlns:rpm=\"http://linux.duke.edu/metadata/rpm\" packages=\"{count}\">\n"
+    );
+    let mut filelists = format!("{header}<filelists xmlns=\"http://linux.duke.edu/metadata/filelists\" packages=\"{count}\">\n");
+    let mut other = format!("{header}<otherdata xmlns=\"http://linux.duke.edu/metadata/other\" packages=\"{count}\">\n");
+    for pkg in &indexed {
+        let info = &pkg.info;
+        primary.push_str(&primary_package(pkg));
+        let opening = format!(
+            "<package pkgid=\"{}\" name=\"{}\" arch=\"{}\">\n  {}\n",
+            pkg.sha256,
+            escape(&info.name),
+            escape(&info.arch),
+            version_element(info)
+        );
+        filelists.push_str(&opening);
+        for file in &info.files {
+            filelists.push_str(&file_element("  ", file));
+        }
+        filelists.push_str("</package>\n");
+        other.push_str(&opening);
+        other.push_str("</package>\n");
+    }
+    primary.push_str("</metadata>\n");
+    filelists.push_str("</filelists>\n");
+    other.push_str("</otherdata>\n");
+    Ok(vec![("primary", primary), ("filelists", filelists), ("other", other)])
+}
+
+/// Compresses each metadata document into `repodata/` and renders the `repomd.xml` pointing at them.
+fn render_repodata(metadata: &[(&str, String)], timestamp: u64) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut files = Vec::new();
+    let mut repomd = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<repomd xmlns=\"http://linux.duke.edu/metadata/repo\" xmlns:rpm=\"http://linux.duke.edu/metadata/rpm\">\n  <revision>{timestamp}</revision>\n"
+    );
+    for (kind, xml) in metadata {
+        let gz = gzip(xml.as_bytes())?;
+        let href = format!("repodata/{kind}.xml.gz");
+        repomd.push_str(&format!(
+            "  <data type=\"{kind}\">\n    <checksum type=\"sha256\">{:x}</checksum>\n    <open-checksum type=\"sha256\">{:x}</open-checksum>\n    <location href=\"{href}\"/>\n    <timestamp>{timestamp}</timestamp>\n    <size>{}</size>\n    <open-size>{}</open-size>\n  </data>\n",
+            Sha256::digest(&gz),
+            Sha256::digest(xml.as_bytes()),
+            gz.len(),
+            xml.len(),
+        ));
+        files.push((href, gz));
+    }
+    repomd.push_str("</repomd>\n");
+    files.push(("repodata/repomd.xml".to_string(), repomd.into_bytes()));
+    Ok(files)
+}
+
+fn sign_args(key: &str) -> Vec<&str> {
+    vec!["--batch", "--yes", "--local-user", key, "--armor", "--detach-sign", "repodata/repomd.xml"]
+}
+
+fn release_yum_repo(ctx: &Context) -> Result<()> {
+    let ch = ctx.config.channels.yum_repo.as_ref().unwrap();
+    let new = new_packages(ctx);
+    if new.is_empty() {
+        println!("[yum-repo] No .rpm packages were built, nothing to add");
+        return Ok(());
+    }
+    let remote = format!("https://github.com/{}.git", ctx.config.project.repo);
+    let message = format!("Add {} {} to the yum repository", ctx.config.project.name, ctx.version);
+    update_hosted_repo("yum-repo", &ch.location, &remote, "yum", &message, |root| {
+        copy_hosted_packages(root, &new)?;
+        let pool = hosted_packages(Some(root), "rpm", &[])?;
+        for (path, data) in render_repodata(&render_metadata(&pool)?, source_date_epoch())? {
+            let dest = root.join(path);
+            std::fs::create_dir_all(dest.parent().unwrap())?;
+            std::fs::write(&dest, data).with_context(|| format!("writing {}", dest.display()))?;
+        }
+        match &ch.gpg_key {
+            Some(key) => {
+                run_cmd("yum-repo", Some(root), "gpg", &sign_args(key))?;
+            }
+            None => {
+                // a stale signature would no longer match the new repomd.xml
+                std::fs::remove_file(root.join("repodata/repomd.xml.asc")).ok();
+            }
+        }
+        println!("[yum-repo] Indexed {} package(s) in {}", pool.len(), root.display());
+        Ok(())
+    })
+}
+
+fn plan_yum_repo(ctx: &Context) -> Result<Plan> {
+    let ch = ctx.config.channels.yum_repo.as_ref().unwrap();
+    let new = new_packages(ctx);
+    let mut plan = Plan::new("yum-repo");
+    let root = ch.location.directory.as_deref().map(Path::new).filter(|d| d.exists());
+    let metadata = render_metadata(&hosted_packages(root, "rpm", &new)?)?;
+    for (path, data) in render_repodata(&metadata, source_date_epoch())? {
+        if !path.ends_with(".gz") {
+            plan.file(&path, String::from_utf8(data)?);
+        }
+    }
+    for (kind, xml) in metadata {
+        plan.file(&format!("repodata/{kind}.xml"), xml);
+    }
+    if let Some(key) = &ch.gpg_key {
+        plan.command("gpg", &sign_args(key));
+    }
+    if let Some(branch) = &ch.location.branch {
+        plan.command("git", &["push", "origin", &format!("HEAD:refs/heads/{branch}")]);
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::test_packages;
+
+    // --- render_metadata tests ---
+
+    #[test]
+    fn dependency_entries_split_version_and_release() {
+        let deps = vec![
+            Dependency {
+                name: "tool".to_string(),
+                flags: Some("EQ"),
+                version: "1.0.0-1".to_string(),
+            },
+            Dependency {
+                name: "glibc".to_string(),
+                flags: Some("GE"),
+                version: "2.28".to_string(),
+            },
+            Dependency {
+                name: "bash".to_string(),
+                flags: None,
+                version: String::new(),
+            },
+        ];
+        assert_eq!(
+            dependency_entries("requires", &deps),
+            "    <rpm:requires>\n\
+             \x20     <rpm:entry name=\"tool\" flags=\"EQ\" epoch=\"0\" ver=\"1.0.0\" rel=\"1\"/>\n\
+             \x20     <rpm:entry name=\"glibc\" flags=\"GE\" epoch=\"0\" ver=\"2.28\"/>\n\
+             \x20     <rpm:entry name=\"bash\"/>\n\
+             \x20   </rpm:requires>\n"
+        );
+        assert_eq!(dependency_entries("provides", &[]), "");
+    }
+
+    #[test]
+    fn render_metadata_lists_every_package_in_the_pool() {
+        let dir = std::env::temp_dir().join(format!("releasor2000-yum-repo-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut pool = Vec::new();
+        for version in ["1.0.0", "1.1.0"] {
+            let rpm = test_packages(&dir, version).into_iter().find(|p| p.format == PackageFormat::Rpm).unwrap();
+            pool.push((format!("Packages/{}", rpm.name()), rpm.path));
+        }
+
+        let metadata = render_metadata(&pool).unwrap();
+        let primary = &metadata[0].1;
+        assert!(primary.contains("packages=\"2\">\n<package type=\"rpm\">\n  <name>tool</name>\n  <arch>x86_64</arch>\n"));
+        assert!(primary.contains("  <version epoch=\"0\" ver=\"1.1.0\" rel=\"1\"/>\n"));
+        assert!(primary.contains("  <location href=\"Packages/tool-1.0.0-1.x86_64.rpm\"/>\n"));
+        assert!(primary.contains("    <rpm:license>MIT</rpm:license>\n"));
+        assert!(primary.contains("      <rpm:entry name=\"tool(x86-64)\" flags=\"EQ\" epoch=\"0\" ver=\"1.0.0\" rel=\"1\"/>\n"));
+        assert!(!primary.contains("rpmlib("));
+        assert!(primary.contains("    <file>/usr/bin/tool</file>\n"));
+        let sha = format!("{:x}", Sha256::digest(std::fs::read(&pool[0].1).unwrap()));
+        assert!(metadata[1].1.contains(&format!("<package pkgid=\"{sha}\" name=\"tool\" arch=\"x86_64\">\n")));
+
+        let files = render_repodata(&metadata, 1_700_000_000).unwrap();
+        let names: Vec<&str> = files.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "repodata/primary.xml.gz",
+                "repodata/filelists.xml.gz",
+                "repodata/other.xml.gz",
+                "repodata/repomd.xml"
+            ]
+        );
+        let repomd = String::from_utf8(files[3].1.clone()).unwrap();
+        assert!(repomd.contains("  <revision>1700000000</revision>\n"));
+        assert!(repomd.contains(&format!(
+            "    <checksum type=\"sha256\">{:x}</checksum>\n    <open-checksum type=\"sha256\">{:x}</open-checksum>\n    <location href=\"repodata/primary.xml.gz\"/>\n",
+            Sha256::digest(&files[0].1),
+            Sha256::digest(primary.as_bytes())
+        )));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
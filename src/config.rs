@@ -1,5 +1,6 @@
 use anyhow::{Context, Result, bail};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 #[derive(Debug, Deserialize)]
@@ -7,13 +8,19 @@ pub struct Config {
     pub project: Project,
     pub build: Build,
     #[serde(default)]
+    pub packages: Packages,
+    #[serde(default)]
     pub channels: Channels,
+    pub signing: Option<Signing>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Project {
     pub name: String,
     pub binary: Option<String>,
+    /// Every binary packed into each archive, when there is more than one
+    #[serde(default)]
+    pub binaries: Vec<String>,
     pub repo: String,
     pub version_command: Option<String>,
 }
@@ -22,8 +29,135 @@ pub struct Project {
 pub struct Build {
     pub command: Option<String>,
     pub artifact: Option<String>,
+    /// Per-binary artifact templates, overriding `artifact`
+    #[serde(default)]
+    pub artifacts: BTreeMap<String, String>,
     pub pre_built_dir: Option<String>,
     pub targets: Vec<String>,
+    /// Digests computed for every archive in addition to SHA-256
+    #[serde(default)]
+    pub digests: Vec<DigestAlgorithm>,
+    /// Archive format for targets without an override
+    #[serde(default)]
+    pub archive_format: ArchiveFormat,
+    /// Per-target formats keyed by glob, e.g. `"*-linux-*" = "tar.xz"`
+    #[serde(default)]
+    pub archive_overrides: BTreeMap<String, ArchiveFormat>,
+    /// Extra files packed next to the binary, as globs relative to the project root
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Directory inside each archive that holds its files, e.g. `{binary}-{version}-{target}`
+    pub archive_prefix: Option<String>,
+    /// Write a SLSA provenance statement, `<archive>.intoto.jsonl`, for every archive
+    #[serde(default)]
+    pub provenance: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    #[default]
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    #[serde(rename = "tar.xz")]
+    TarXz,
+    #[serde(rename = "tar.zst")]
+    TarZst,
+    #[serde(rename = "zip")]
+    Zip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha512,
+    Blake3,
+}
+
+/// Native packages built next to the archives and uploaded with them
+#[derive(Debug, Default, Deserialize)]
+pub struct Packages {
+    pub deb: Option<DebPackage>,
+    pub rpm: Option<RpmPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DebPackage {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Defaults to the project name
+    pub package_name: Option<String>,
+    /// `Name <email>`
+    pub maintainer: String,
+    /// One-line summary, defaults to the project name
+    pub description: Option<String>,
+    #[serde(default)]
+    pub depends: Vec<String>,
+}
+
+impl DebPackage {
+    pub fn package_name(&self, project: &Project) -> String {
+        self.package_name.clone().unwrap_or_else(|| project.name.to_lowercase())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RpmPackage {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Defaults to the lowercased project name
+    pub package_name: Option<String>,
+    /// One-line summary, defaults to the project name
+    pub summary: Option<String>,
+    /// Defaults to the summary
+    pub description: Option<String>,
+    /// Defaults to `Unspecified`
+    pub license: Option<String>,
+    /// Either `name` or `name <op> version`, e.g. `glibc >= 2.28`
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(default = "default_rpm_release")]
+    pub release: String,
+}
+
+fn default_rpm_release() -> String {
+    "1".to_string()
+}
+
+impl RpmPackage {
+    pub fn package_name(&self, project: &Project) -> String {
+        self.package_name.clone().unwrap_or_else(|| project.name.to_lowercase())
+    }
+}
+
+/// Detached signatures for every archive and checksum manifest the github channel uploads.
+#[derive(Debug, Deserialize)]
+pub struct Signing {
+    pub method: SigningMethod,
+    /// Secret key file (an unencrypted minisign key, or an SSH private key without a passphrase),
+    /// or the GPG key id
+    pub key: String,
+    /// Public key the install script verifies with: minisign's `RW...` key, an SSH public key
+    /// line, or an ASCII-armored GPG public key
+    pub public_key: Option<String>,
+    /// `ssh-keygen -Y` namespace
+    #[serde(default = "default_ssh_namespace")]
+    pub namespace: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningMethod {
+    /// `<asset>.minisig`, made by `minisign -S`
+    Minisign,
+    /// `<asset>.sig`, made by `ssh-keygen -Y sign`
+    Ssh,
+    /// `<asset>.asc`, made by `gpg --detach-sign`
+    Gpg,
+}
+
+fn default_ssh_namespace() -> String {
+    "file".to_string()
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -33,12 +167,47 @@ pub struct Channels {
     pub cargo: Option<CargoChannel>,
     pub curl: Option<CurlChannel>,
     pub nix: Option<NixChannel>,
+    pub scoop: Option<ScoopChannel>,
+    pub winget: Option<WingetChannel>,
+    pub aur: Option<AurChannel>,
+    #[serde(rename = "apt-repo")]
+    pub apt_repo: Option<AptRepoChannel>,
+    #[serde(rename = "yum-repo")]
+    pub yum_repo: Option<YumRepoChannel>,
+    #[serde(default)]
+    pub plugin: BTreeMap<String, PluginChannel>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct GitHubChannel {
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Upload a checksum manifest covering every release asset
+    #[serde(default = "default_true")]
+    pub checksums: bool,
+    #[serde(default = "default_checksum_file")]
+    pub checksum_file: String,
+    #[serde(default)]
+    pub checksum_format: ChecksumFormat,
+    /// Also upload a `<archive>.sha256` next to each archive
+    #[serde(default)]
+    pub per_archive_checksums: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumFormat {
+    /// `<hash>  <file>`, as read by `sha256sum -c`
+    #[default]
+    Gnu,
+    /// `SHA256 (<file>) = <hash>`, as read by `shasum -c` and BSD `sha256 -c`
+    Bsd,
+    /// `{"<file>": "<hash>"}`
+    Json,
+}
+
+fn default_checksum_file() -> String {
+    "SHA256SUMS".to_string()
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,7 +215,37 @@ pub struct HomebrewChannel {
     #[serde(default = "default_true")]
     pub enabled: bool,
     pub tap: String,
+    #[serde(default)]
+    pub kind: HomebrewKind,
+    /// Also names the cask
     pub formula_name: Option<String>,
+    /// Defaults to `package.description` in Cargo.toml, then the formula name
+    pub description: Option<String>,
+    /// SPDX expression, defaults to `package.license` in Cargo.toml
+    pub license: Option<String>,
+    pub caveats: Option<String>,
+    /// Formulae the binary needs at runtime, e.g. `["openssl@3"]`
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Command run by `brew test`, defaults to `#{bin}/<binary> --version`
+    pub test: Option<String>,
+    /// Arguments that make the binary print a completion script for the shell name appended
+    /// to them, e.g. `["completions"]`
+    pub generate_completions: Option<Vec<String>>,
+    /// App bundle inside the macOS archives that a cask moves to `/Applications`, e.g. `"Foo.app"`
+    pub app: Option<String>,
+    #[serde(flatten)]
+    pub update: RepoUpdate,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HomebrewKind {
+    /// `Formula/<name>.rb`, installing the binaries on macOS and Linux
+    #[default]
+    Formula,
+    /// `Casks/<name>.rb`, installing the macOS archives' app bundle and binaries
+    Cask,
 }
 
 #[derive(Debug, Deserialize)]
@@ -60,6 +259,9 @@ pub struct CargoChannel {
 pub struct CurlChannel {
     #[serde(default = "default_true")]
     pub enabled: bool,
+    /// Make `install.sh` check the archive's signature with `[signing] public_key` before installing
+    #[serde(default)]
+    pub verify_signatures: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -67,6 +269,200 @@ pub struct NixChannel {
     #[serde(default = "default_true")]
     pub enabled: bool,
     pub flake_repo: Option<String>,
+    #[serde(flatten)]
+    pub update: RepoUpdate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScoopChannel {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Bucket repository (`owner/scoop-bucket`)
+    pub bucket: String,
+    pub manifest_name: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WingetChannel {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub publisher: String,
+    /// Defaults to the project name
+    pub package_name: Option<String>,
+    /// Defaults to `<Publisher>.<PackageName>` without spaces
+    pub package_identifier: Option<String>,
+    pub license: String,
+    pub short_description: String,
+    /// Fork of `microsoft/winget-pkgs` to push the manifests to; without it they are only written to staging
+    pub fork: Option<String>,
+    /// Defaults to `<PackageIdentifier>-<version>`
+    pub branch: Option<String>,
+}
+
+impl WingetChannel {
+    pub fn package_name<'a>(&'a self, project: &'a Project) -> &'a str {
+        self.package_name.as_deref().unwrap_or(&project.name)
+    }
+
+    pub fn package_identifier(&self, project: &Project) -> String {
+        match &self.package_identifier {
+            Some(id) => id.clone(),
+            None => format!("{}.{}", self.publisher, self.package_name(project)).replace(' ', ""),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AurChannel {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Defaults to `<project name>-bin`
+    pub package_name: Option<String>,
+    /// Git remote of the package, defaults to `ssh://aur@aur.archlinux.org/<package_name>.git`
+    pub remote: Option<String>,
+    /// `Name <email>` written to the PKGBUILD header
+    pub maintainer: Option<String>,
+    pub description: Option<String>,
+    /// SPDX license identifiers, e.g. `["MIT"]`
+    pub license: Vec<String>,
+    #[serde(default)]
+    pub depends: Vec<String>,
+}
+
+impl AurChannel {
+    pub fn package_name(&self, project: &Project) -> String {
+        self.package_name.clone().unwrap_or_else(|| format!("{}-bin", project.name))
+    }
+
+    pub fn remote(&self, project: &Project) -> String {
+        match &self.remote {
+            Some(remote) => remote.clone(),
+            None => format!("ssh://aur@aur.archlinux.org/{}.git", self.package_name(project)),
+        }
+    }
+}
+
+/// How a channel changes the repository it writes to (a tap or flake repository).
+#[derive(Debug, Default, Deserialize)]
+pub struct RepoUpdate {
+    #[serde(default)]
+    pub mode: UpdateMode,
+    /// Enable auto-merge on the pull request with this merge method
+    pub auto_merge: Option<MergeMethod>,
+}
+
+impl RepoUpdate {
+    fn validate(&self, channel: &str) -> Result<()> {
+        if self.auto_merge.is_some() && self.mode != UpdateMode::PullRequest {
+            bail!("channels.{channel}: auto_merge requires mode = \"pull_request\"");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateMode {
+    /// Commit straight to the default branch
+    #[default]
+    Commit,
+    /// Commit to a `releasor2000/<name>-<version>` branch and open a pull request
+    PullRequest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    /// GraphQL `PullRequestMergeMethod` value
+    pub fn graphql_name(self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "MERGE",
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Rebase => "REBASE",
+        }
+    }
+}
+
+/// Where a self-hosted package repository is kept: a local directory, or a directory on a
+/// branch of the project repository.
+#[derive(Debug, Deserialize)]
+pub struct HostedRepo {
+    pub directory: Option<String>,
+    /// e.g. `gh-pages`
+    pub branch: Option<String>,
+    /// Directory within `branch`, defaults to `apt` or `yum`
+    pub path: Option<String>,
+}
+
+impl HostedRepo {
+    fn validate(&self, channel: &str) -> Result<()> {
+        if self.directory.is_some() == self.branch.is_some() {
+            bail!("channels.{channel}: exactly one of directory or branch is required");
+        }
+        if self.directory.is_some() && self.path.is_some() {
+            bail!("channels.{channel}: path only applies to branch");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AptRepoChannel {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub location: HostedRepo,
+    #[serde(default = "default_apt_distribution")]
+    pub distribution: String,
+    #[serde(default = "default_apt_component")]
+    pub component: String,
+    /// GPG key that signs `Release` into `InRelease` and `Release.gpg`
+    pub gpg_key: Option<String>,
+}
+
+fn default_apt_distribution() -> String {
+    "stable".to_string()
+}
+
+fn default_apt_component() -> String {
+    "main".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct YumRepoChannel {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub location: HostedRepo,
+    /// GPG key that signs `repodata/repomd.xml` into `repomd.xml.asc`
+    pub gpg_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PluginChannel {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Executable to run, defaults to `releasor2000-channel-<name>`
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_plugin_depends_on")]
+    pub depends_on: Vec<String>,
+    /// Any other keys are passed through to the plugin
+    #[serde(flatten)]
+    pub options: toml::Table,
+}
+
+fn default_plugin_depends_on() -> Vec<String> {
+    vec!["github".to_string()]
 }
 
 fn default_true() -> bool {
@@ -74,8 +470,42 @@ fn default_true() -> bool {
 }
 
 impl Project {
+    /// The primary binary, which names the archives.
     pub fn binary(&self) -> &str {
-        self.binary.as_deref().unwrap_or(&self.name)
+        self.binary
+            .as_deref()
+            .or(self.binaries.first().map(|b| b.as_str()))
+            .unwrap_or(&self.name)
+    }
+
+    /// Every binary packed into each archive.
+    pub fn binaries(&self) -> Vec<&str> {
+        if self.binaries.is_empty() {
+            vec![self.binary()]
+        } else {
+            self.binaries.iter().map(|b| b.as_str()).collect()
+        }
+    }
+}
+
+impl Build {
+    /// Artifact path template for `binary`, if any.
+    pub fn artifact_for(&self, binary: &str) -> Option<&str> {
+        self.artifacts.get(binary).or(self.artifact.as_ref()).map(|a| a.as_str())
+    }
+
+    /// The longest matching `archive_overrides` pattern wins; Windows targets default to zip.
+    pub fn archive_format_for(&self, target: &str) -> ArchiveFormat {
+        let matched = self
+            .archive_overrides
+            .iter()
+            .filter(|(pattern, _)| glob::Pattern::new(pattern).is_ok_and(|p| p.matches(target)))
+            .max_by_key(|(pattern, _)| pattern.len());
+        match matched {
+            Some((_, format)) => *format,
+            None if crate::channels::is_windows(target) => ArchiveFormat::Zip,
+            None => self.archive_format,
+        }
     }
 }
 
@@ -84,6 +514,7 @@ pub fn generate_template(project_name: &str) -> String {
         r#"[project]
 name = "{project_name}"
 # binary = "{project_name}"  # defaults to project name
+# binaries = ["{project_name}", "{project_name}-daemon"]  # pack several binaries per archive
 repo = "owner/{project_name}"
 # version_command = "git describe --tags --abbrev=0"
 
@@ -96,21 +527,83 @@ targets = [
     "x86_64-unknown-linux-gnu",
     "aarch64-unknown-linux-gnu",
 ]
+# digests = ["sha512", "blake3"]  # in addition to sha256
+# archive_format = "tar.gz"  # tar.gz, tar.xz, tar.zst or zip
+# include = ["LICENSE", "README.md", "completions/*", "man/*.1"]
+# archive_prefix = "{{binary}}-{{version}}-{{target}}"
+# provenance = false  # also upload a SLSA provenance <archive>.intoto.jsonl
+# [build.archive_overrides]
+# "*-windows-*" = "zip"  # the default for Windows targets
+
+# [packages.deb]  # .deb packages for x86_64/aarch64 Linux targets
+# maintainer = "Your Name <you@example.com>"
+# description = ""
+# depends = ["libc6"]
+
+# [packages.rpm]  # .rpm packages for x86_64/aarch64 Linux targets
+# summary = ""
+# license = "MIT"
+# requires = ["glibc >= 2.28"]
+
+# [signing]  # signs every archive and checksum manifest
+# method = "minisign"  # or "ssh" or "gpg"
+# key = "release.key"  # secret key file, or the GPG key id
+# public_key = "RW..."  # lets install.sh verify downloads
 
 [channels.github]
 enabled = true
+# checksum_file = "SHA256SUMS"
+# checksum_format = "gnu"  # gnu, bsd or json
+# per_archive_checksums = false
 
 # [channels.homebrew]
 # tap = "owner/homebrew-tap"
 # formula_name = "{project_name}"
+# license = "MIT"                       # defaults to Cargo.toml's package.license
+# depends_on = ["openssl@3"]
+# generate_completions = ["completions"]  # runs `{project_name} completions <shell>`
+# kind = "formula"                      # or "cask" for app bundles in the macOS archives
+# app = "{project_name}.app"            # cask only
+# mode = "commit"                       # or "pull_request" to open a PR against the tap
+# auto_merge = "squash"                 # pull_request only: merge, squash or rebase
 
 # [channels.cargo]
 # crate_name = "{project_name}"
 
 # [channels.curl]
+# verify_signatures = false  # needs signing.public_key
 
 # [channels.nix]
 # flake_repo = "owner/nix-repo"  # defaults to project repo
+# mode = "commit"                # or "pull_request"
+
+# [channels.scoop]
+# bucket = "owner/scoop-bucket"
+# manifest_name = "{project_name}"
+# description = ""
+# license = "MIT"
+
+# [channels.winget]
+# publisher = "Owner"
+# license = "MIT"
+# short_description = ""
+# fork = "owner/winget-pkgs"  # leave out to only write the manifests to target/release-staging
+
+# [channels.aur]
+# license = ["MIT"]
+# package_name = "{project_name}-bin"
+# maintainer = "Your Name <you@example.com>"
+
+# [channels.apt-repo]  # needs [packages.deb]
+# branch = "gh-pages"  # or directory = "dist/apt"
+# gpg_key = "you@example.com"
+
+# [channels.yum-repo]  # needs [packages.rpm]
+# branch = "gh-pages"  # or directory = "dist/yum"
+# gpg_key = "you@example.com"
+
+# [channels.plugin.internal]
+# command = "releasor2000-channel-internal"  # defaults to releasor2000-channel-<name>
 "#
     )
 }
@@ -135,43 +628,67 @@ impl Config {
         if self.build.command.is_none() && self.build.pre_built_dir.is_none() {
             bail!("one of build.command or build.pre_built_dir is required");
         }
-        if self.build.command.is_some() && self.build.artifact.is_none() {
-            bail!("build.artifact is required when build.command is set");
+        if self.build.command.is_some() {
+            for binary in self.project.binaries() {
+                if self.build.artifact_for(binary).is_none() {
+                    bail!("build.artifact is required when build.command is set (no artifact for {binary})");
+                }
+            }
         }
         if self.build.targets.is_empty() {
             bail!("build.targets must not be empty");
         }
-        Ok(())
-    }
-
-    pub fn enabled_channels(&self) -> Vec<&str> {
-        let mut names = Vec::new();
-        if let Some(ch) = &self.channels.github {
-            if ch.enabled {
-                names.push("github");
+        if let Some(winget) = &self.channels.winget {
+            let id = winget.package_identifier(&self.project);
+            if id.split('.').count() < 2 || id.split('.').any(str::is_empty) {
+                bail!("channels.winget: package identifier {id:?} must look like Publisher.Name");
             }
         }
-        if let Some(ch) = &self.channels.homebrew {
-            if ch.enabled {
-                names.push("homebrew");
-            }
+        if self.channels.curl.as_ref().is_some_and(|curl| curl.verify_signatures)
+            && self.signing.as_ref().and_then(|s| s.public_key.as_ref()).is_none()
+        {
+            bail!("channels.curl.verify_signatures requires signing.public_key");
         }
-        if let Some(ch) = &self.channels.cargo {
-            if ch.enabled {
-                names.push("cargo");
+        if let Some(homebrew) = &self.channels.homebrew {
+            homebrew.update.validate("homebrew")?;
+        }
+        if let Some(nix) = &self.channels.nix {
+            nix.update.validate("nix")?;
+        }
+        if let Some(apt) = &self.channels.apt_repo {
+            apt.location.validate("apt-repo")?;
+            if self.packages.deb.is_none() {
+                bail!("channels.apt-repo requires [packages.deb]");
             }
         }
-        if let Some(ch) = &self.channels.curl {
-            if ch.enabled {
-                names.push("curl");
+        if let Some(yum) = &self.channels.yum_repo {
+            yum.location.validate("yum-repo")?;
+            if self.packages.rpm.is_none() {
+                bail!("channels.yum-repo requires [packages.rpm]");
             }
         }
-        if let Some(ch) = &self.channels.nix {
-            if ch.enabled {
-                names.push("nix");
+        for pattern in &self.build.include {
+            glob::Pattern::new(pattern).with_context(|| format!("build.include: invalid pattern {pattern:?}"))?;
+        }
+        for pattern in self.build.archive_overrides.keys() {
+            glob::Pattern::new(pattern)
+                .with_context(|| format!("build.archive_overrides: invalid target pattern {pattern:?}"))?;
+        }
+        let builtin = crate::channels::builtin_channels();
+        for name in self.channels.plugin.keys() {
+            if builtin.iter().any(|ch| ch.name() == name) {
+                bail!("plugin channel {name} conflicts with the built-in channel of the same name");
             }
         }
-        names
+        Ok(())
+    }
+
+    pub fn enabled_channels(&self) -> Vec<String> {
+        crate::channels::registry(self)
+            .iter()
+            .filter(|ch| ch.enabled(self))
+            .map(|ch| ch.name().to_string())
+            .collect()
     }
 }
 
@@ -199,12 +716,133 @@ targets = ["x86_64-apple-darwin"]
         assert_eq!(config.project.name, "myapp");
         assert_eq!(config.project.repo, "owner/repo");
         assert_eq!(config.project.binary(), "myapp");
+        assert_eq!(config.project.binaries(), vec!["myapp"]);
         assert!(config.project.binary.is_none());
         assert!(config.project.version_command.is_none());
         assert_eq!(config.build.targets.len(), 1);
+        assert!(config.build.digests.is_empty());
         assert!(config.enabled_channels().is_empty());
     }
 
+    #[test]
+    fn extra_digests() {
+        let toml = r#"
+[project]
+name = "myapp"
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/bin"
+targets = ["x86_64-apple-darwin"]
+digests = ["sha512", "blake3"]
+"#;
+        let config = Config::parse(toml).unwrap();
+        assert_eq!(
+            config.build.digests,
+            vec![DigestAlgorithm::Sha512, DigestAlgorithm::Blake3]
+        );
+    }
+
+    #[test]
+    fn archive_format_defaults() {
+        let config = Config::parse(&minimal_toml()).unwrap();
+        assert_eq!(config.build.archive_format_for("x86_64-apple-darwin"), ArchiveFormat::TarGz);
+        assert_eq!(config.build.archive_format_for("x86_64-pc-windows-msvc"), ArchiveFormat::Zip);
+    }
+
+    #[test]
+    fn archive_format_overrides() {
+        let toml = r#"
+[project]
+name = "myapp"
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/bin"
+targets = ["x86_64-apple-darwin"]
+archive_format = "tar.xz"
+
+[build.archive_overrides]
+"*-linux-*" = "tar.zst"
+"aarch64-unknown-linux-*" = "tar.gz"
+"#;
+        let build = Config::parse(toml).unwrap().build;
+        assert_eq!(build.archive_format_for("x86_64-apple-darwin"), ArchiveFormat::TarXz);
+        assert_eq!(build.archive_format_for("x86_64-unknown-linux-gnu"), ArchiveFormat::TarZst);
+        assert_eq!(build.archive_format_for("aarch64-unknown-linux-musl"), ArchiveFormat::TarGz);
+        assert_eq!(build.archive_format_for("x86_64-pc-windows-gnu"), ArchiveFormat::Zip);
+    }
+
+    #[test]
+    fn include_and_archive_prefix() {
+        let toml = format!(
+            "{}include = [\"LICENSE\", \"completions/*\"]\narchive_prefix = \"{{binary}}-{{version}}-{{target}}\"\n",
+            minimal_toml()
+        );
+        let build = Config::parse(&toml).unwrap().build;
+        assert_eq!(build.include, vec!["LICENSE", "completions/*"]);
+        assert_eq!(build.archive_prefix.as_deref(), Some("{binary}-{version}-{target}"));
+        assert!(Config::parse(&minimal_toml()).unwrap().build.include.is_empty());
+    }
+
+    #[test]
+    fn provenance_is_opt_in() {
+        assert!(!Config::parse(&minimal_toml()).unwrap().build.provenance);
+        let build = Config::parse(&format!("{}provenance = true\n", minimal_toml())).unwrap().build;
+        assert!(build.provenance);
+    }
+
+    #[test]
+    fn invalid_archive_override_pattern_rejected() {
+        let toml = format!("{}\n[build.archive_overrides]\n\"[x\" = \"zip\"\n", minimal_toml());
+        let err = Config::parse(&toml).unwrap_err();
+        assert!(format!("{err:#}").contains("invalid target pattern"), "got: {err:#}");
+    }
+
+    #[test]
+    fn multiple_binaries_with_artifact_overrides() {
+        let toml = r#"
+[project]
+name = "myapp"
+binaries = ["myapp", "myapp-daemon"]
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/{binary}"
+targets = ["x86_64-apple-darwin"]
+
+[build.artifacts]
+myapp-daemon = "daemon/out/{target}/daemond"
+"#;
+        let config = Config::parse(toml).unwrap();
+        assert_eq!(config.project.binary(), "myapp");
+        assert_eq!(config.project.binaries(), vec!["myapp", "myapp-daemon"]);
+        assert_eq!(config.build.artifact_for("myapp"), Some("out/{binary}"));
+        assert_eq!(config.build.artifact_for("myapp-daemon"), Some("daemon/out/{target}/daemond"));
+    }
+
+    #[test]
+    fn every_binary_needs_an_artifact() {
+        let toml = r#"
+[project]
+name = "myapp"
+binaries = ["myapp", "myapp-daemon"]
+repo = "owner/repo"
+
+[build]
+command = "make"
+targets = ["x86_64-apple-darwin"]
+
+[build.artifacts]
+myapp = "out/myapp"
+"#;
+        let err = Config::parse(toml).unwrap_err();
+        assert!(err.to_string().contains("no artifact for myapp-daemon"), "got: {err}");
+    }
+
     #[test]
     fn binary_defaults_to_name() {
         let config = Config::parse(&minimal_toml()).unwrap();
@@ -361,6 +999,96 @@ crate_name = "myapp"
         assert!(config.enabled_channels().is_empty());
     }
 
+    #[test]
+    fn plugin_channels_are_enabled_after_builtins() {
+        let toml = r#"
+[project]
+name = "myapp"
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/bin"
+targets = ["x86_64-apple-darwin"]
+
+[channels.plugin.internal]
+command = "./publish-internal"
+bucket = "releases"
+
+[channels.github]
+"#;
+        let config = Config::parse(toml).unwrap();
+        assert_eq!(config.enabled_channels(), vec!["github", "internal"]);
+        let plugin = &config.channels.plugin["internal"];
+        assert_eq!(plugin.command.as_deref(), Some("./publish-internal"));
+        assert_eq!(plugin.depends_on, vec!["github"]);
+        assert_eq!(plugin.options["bucket"].as_str(), Some("releases"));
+    }
+
+    #[test]
+    fn plugin_channel_cannot_shadow_builtin() {
+        let toml = r#"
+[project]
+name = "myapp"
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/bin"
+targets = ["x86_64-apple-darwin"]
+
+[channels.plugin.homebrew]
+"#;
+        let err = Config::parse(toml).unwrap_err();
+        assert!(err.to_string().contains("conflicts with the built-in channel"), "got: {err}");
+    }
+
+    #[test]
+    fn github_checksum_defaults() {
+        let toml = r#"
+[project]
+name = "myapp"
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/bin"
+targets = ["x86_64-apple-darwin"]
+
+[channels.github]
+"#;
+        let config = Config::parse(toml).unwrap();
+        let github = config.channels.github.unwrap();
+        assert!(github.checksums);
+        assert_eq!(github.checksum_file, "SHA256SUMS");
+        assert_eq!(github.checksum_format, ChecksumFormat::Gnu);
+        assert!(!github.per_archive_checksums);
+    }
+
+    #[test]
+    fn github_checksum_format_override() {
+        let toml = r#"
+[project]
+name = "myapp"
+repo = "owner/repo"
+
+[build]
+command = "make"
+artifact = "out/bin"
+targets = ["x86_64-apple-darwin"]
+
+[channels.github]
+checksum_file = "checksums.txt"
+checksum_format = "bsd"
+per_archive_checksums = true
+"#;
+        let config = Config::parse(toml).unwrap();
+        let github = config.channels.github.unwrap();
+        assert_eq!(github.checksum_file, "checksums.txt");
+        assert_eq!(github.checksum_format, ChecksumFormat::Bsd);
+        assert!(github.per_archive_checksums);
+    }
+
     #[test]
     fn generate_template_parses_successfully() {
         let template = generate_template("myapp");
@@ -423,4 +1151,166 @@ flake_repo = "owner/nix-repo"
             Some("myapp")
         );
     }
+
+    #[test]
+    fn parse_scoop_channel() {
+        let toml = format!("{}\n[channels.scoop]\nbucket = \"owner/scoop-bucket\"\nlicense = \"MIT\"\n", minimal_toml());
+        let config = Config::parse(&toml).unwrap();
+        let scoop = config.channels.scoop.as_ref().unwrap();
+        assert_eq!(scoop.bucket, "owner/scoop-bucket");
+        assert_eq!(scoop.license.as_deref(), Some("MIT"));
+        assert!(scoop.manifest_name.is_none());
+        assert!(config.enabled_channels().contains(&"scoop".to_string()));
+    }
+
+    #[test]
+    fn winget_package_identifier_defaults_to_publisher_and_name() {
+        let toml = format!(
+            "{}\n[channels.winget]\npublisher = \"Acme Corp\"\nlicense = \"MIT\"\nshort_description = \"An app\"\n",
+            minimal_toml()
+        );
+        let config = Config::parse(&toml).unwrap();
+        let winget = config.channels.winget.as_ref().unwrap();
+        assert_eq!(winget.package_name(&config.project), "myapp");
+        assert_eq!(winget.package_identifier(&config.project), "AcmeCorp.myapp");
+    }
+
+    #[test]
+    fn validate_rejects_winget_identifier_without_publisher() {
+        let toml = format!(
+            "{}\n[channels.winget]\npublisher = \"Acme\"\npackage_identifier = \"myapp\"\nlicense = \"MIT\"\nshort_description = \"An app\"\n",
+            minimal_toml()
+        );
+        let err = Config::parse(&toml).unwrap_err();
+        assert!(err.to_string().contains("Publisher.Name"));
+    }
+
+    #[test]
+    fn aur_package_name_and_remote_default_to_bin_package() {
+        let toml = format!("{}\n[channels.aur]\nlicense = [\"MIT\"]\n", minimal_toml());
+        let config = Config::parse(&toml).unwrap();
+        let aur = config.channels.aur.as_ref().unwrap();
+        assert_eq!(aur.package_name(&config.project), "myapp-bin");
+        assert_eq!(aur.remote(&config.project), "ssh://aur@aur.archlinux.org/myapp-bin.git");
+        assert!(aur.depends.is_empty());
+    }
+
+    #[test]
+    fn parse_deb_package() {
+        let toml = format!(
+            "{}\n[packages.deb]\nmaintainer = \"Me <me@example.com>\"\ndepends = [\"libc6\"]\n",
+            minimal_toml()
+        );
+        let config = Config::parse(&toml).unwrap();
+        let deb = config.packages.deb.as_ref().unwrap();
+        assert!(deb.enabled);
+        assert_eq!(deb.maintainer, "Me <me@example.com>");
+        assert_eq!(deb.depends, vec!["libc6"]);
+        assert_eq!(deb.package_name(&config.project), "myapp");
+        assert!(Config::parse(&minimal_toml()).unwrap().packages.deb.is_none());
+    }
+
+    #[test]
+    fn parse_rpm_package_defaults() {
+        let toml = format!("{}\n[packages.rpm]\nrequires = [\"glibc >= 2.28\"]\n", minimal_toml());
+        let config = Config::parse(&toml).unwrap();
+        let rpm = config.packages.rpm.as_ref().unwrap();
+        assert!(rpm.enabled);
+        assert_eq!(rpm.release, "1");
+        assert_eq!(rpm.requires, vec!["glibc >= 2.28"]);
+        assert_eq!(rpm.package_name(&config.project), "myapp");
+        assert!(rpm.license.is_none());
+    }
+
+    #[test]
+    fn parse_hosted_repo_channels() {
+        let toml = format!(
+            "{}\n[packages.deb]\nmaintainer = \"Me <me@example.com>\"\n[packages.rpm]\n\
+             [channels.apt-repo]\nbranch = \"gh-pages\"\n[channels.yum-repo]\ndirectory = \"dist/yum\"\n",
+            minimal_toml()
+        );
+        let config = Config::parse(&toml).unwrap();
+        let apt = config.channels.apt_repo.as_ref().unwrap();
+        assert_eq!(apt.location.branch.as_deref(), Some("gh-pages"));
+        assert_eq!((apt.distribution.as_str(), apt.component.as_str()), ("stable", "main"));
+        assert!(apt.gpg_key.is_none());
+        let yum = config.channels.yum_repo.as_ref().unwrap();
+        assert_eq!(yum.location.directory.as_deref(), Some("dist/yum"));
+    }
+
+    #[test]
+    fn validate_rejects_hosted_repo_without_single_location_or_package() {
+        let deb = "[packages.deb]\nmaintainer = \"Me <me@example.com>\"\n";
+        for (extra, message) in [
+            (format!("{deb}[channels.apt-repo]\n"), "exactly one of directory or branch"),
+            (format!("{deb}[channels.apt-repo]\ndirectory = \"a\"\nbranch = \"b\"\n"), "exactly one of directory or branch"),
+            ("[channels.apt-repo]\nbranch = \"gh-pages\"\n".to_string(), "requires [packages.deb]"),
+            ("[channels.yum-repo]\nbranch = \"gh-pages\"\n".to_string(), "requires [packages.rpm]"),
+        ] {
+            let err = Config::parse(&format!("{}\n{extra}", minimal_toml())).unwrap_err();
+            assert!(err.to_string().contains(message), "got: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_homebrew_formula_options() {
+        let toml = format!(
+            "{}\n[channels.homebrew]\ntap = \"owner/tap\"\nlicense = \"MIT\"\ndepends_on = [\"openssl@3\"]\ngenerate_completions = [\"completions\"]\n",
+            minimal_toml()
+        );
+        let config = Config::parse(&toml).unwrap();
+        let brew = config.channels.homebrew.as_ref().unwrap();
+        assert_eq!(brew.license.as_deref(), Some("MIT"));
+        assert_eq!(brew.depends_on, vec!["openssl@3"]);
+        assert_eq!(brew.generate_completions.as_deref(), Some(&["completions".to_string()][..]));
+        assert!(brew.description.is_none() && brew.caveats.is_none() && brew.test.is_none());
+        assert_eq!(brew.kind, HomebrewKind::Formula);
+    }
+
+    #[test]
+    fn parse_homebrew_cask() {
+        let toml = format!("{}\n[channels.homebrew]\ntap = \"owner/tap\"\nkind = \"cask\"\napp = \"Tool.app\"\n", minimal_toml());
+        let config = Config::parse(&toml).unwrap();
+        let brew = config.channels.homebrew.as_ref().unwrap();
+        assert_eq!(brew.kind, HomebrewKind::Cask);
+        assert_eq!(brew.app.as_deref(), Some("Tool.app"));
+        assert_eq!(brew.update.mode, UpdateMode::Commit);
+    }
+
+    #[test]
+    fn parse_signing() {
+        let toml = format!(
+            "{}\n[signing]\nmethod = \"minisign\"\nkey = \"release.key\"\npublic_key = \"RWQ\"\n[channels.curl]\nverify_signatures = true\n",
+            minimal_toml()
+        );
+        let config = Config::parse(&toml).unwrap();
+        let signing = config.signing.as_ref().unwrap();
+        assert_eq!(signing.method, SigningMethod::Minisign);
+        assert_eq!(signing.key, "release.key");
+        assert_eq!(signing.namespace, "file");
+        assert!(config.channels.curl.as_ref().unwrap().verify_signatures);
+
+        let toml = format!("{}\n[signing]\nmethod = \"ssh\"\nkey = \"id_ed25519\"\n[channels.curl]\nverify_signatures = true\n", minimal_toml());
+        let err = Config::parse(&toml).unwrap_err();
+        assert!(err.to_string().contains("requires signing.public_key"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_pull_request_mode() {
+        let toml = format!(
+            "{}\n[channels.homebrew]\ntap = \"owner/tap\"\nmode = \"pull_request\"\nauto_merge = \"squash\"\n[channels.nix]\nmode = \"pull_request\"\n",
+            minimal_toml()
+        );
+        let config = Config::parse(&toml).unwrap();
+        let brew = config.channels.homebrew.as_ref().unwrap();
+        assert_eq!(brew.update.mode, UpdateMode::PullRequest);
+        assert_eq!(brew.update.auto_merge, Some(MergeMethod::Squash));
+        let nix = config.channels.nix.as_ref().unwrap();
+        assert_eq!(nix.update.mode, UpdateMode::PullRequest);
+        assert!(nix.update.auto_merge.is_none());
+
+        let toml = format!("{}\n[channels.nix]\nauto_merge = \"merge\"\n", minimal_toml());
+        let err = Config::parse(&toml).unwrap_err();
+        assert!(err.to_string().contains("auto_merge requires mode"), "got: {err}");
+    }
 }
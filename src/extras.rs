@@ -0,0 +1,84 @@
+//! Recognising an archive's extra files by name: shell completions and man pages.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+/// Shell completion scripts among an archive's extra files, recognised by name
+/// (`*.bash`, `*.fish`, `*.zsh` or zsh's `_<name>`), with the command each one completes.
+pub fn completions(files: &[String]) -> Vec<(Shell, &str, &str)> {
+    files
+        .iter()
+        .filter_map(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            let (shell, command) = if let Some(cmd) = name.strip_suffix(".bash") {
+                (Shell::Bash, cmd)
+            } else if let Some(cmd) = name.strip_suffix(".fish") {
+                (Shell::Fish, cmd)
+            } else if let Some(cmd) = name.strip_suffix(".zsh") {
+                (Shell::Zsh, cmd.trim_start_matches('_'))
+            } else if let Some(cmd) = name.strip_prefix('_').filter(|n| !n.contains('.')) {
+                (Shell::Zsh, cmd)
+            } else {
+                return None;
+            };
+            Some((shell, path.as_str(), command))
+        })
+        .collect()
+}
+
+/// Man pages among an archive's extra files, with their section (`tool.1` is section 1).
+pub fn man_pages(files: &[String]) -> Vec<(char, &str)> {
+    files
+        .iter()
+        .filter_map(|path| {
+            let (_, ext) = path.rsplit('/').next().unwrap_or(path).rsplit_once('.')?;
+            let mut chars = ext.chars();
+            match (chars.next(), chars.next()) {
+                (Some(section @ '1'..='9'), None) => Some((section, path.as_str())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_recognised_by_name() {
+        let files: Vec<String> = ["completions/tool.bash", "completions/_tool", "completions/tool.fish", "LICENSE", "man/tool.1"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        assert_eq!(
+            completions(&files),
+            vec![
+                (Shell::Bash, "completions/tool.bash", "tool"),
+                (Shell::Zsh, "completions/_tool", "tool"),
+                (Shell::Fish, "completions/tool.fish", "tool"),
+            ]
+        );
+        assert_eq!(man_pages(&files), vec![('1', "man/tool.1")]);
+    }
+
+    #[test]
+    fn man_pages_need_a_single_digit_section() {
+        let files: Vec<String> = ["README.md", "tool.10", "tool.5", "tool.md"].iter().map(|f| f.to_string()).collect();
+        assert_eq!(man_pages(&files), vec![('5', "tool.5")]);
+    }
+}
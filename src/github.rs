@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::OnceLock;
+
+const USER_AGENT: &str = concat!("releasor2000/", env!("CARGO_PKG_VERSION"));
+pub const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// An error talking to GitHub, keeping the HTTP status and GitHub's own error message.
+#[derive(Debug)]
+pub enum GitHubError {
+    /// GitHub answered with a non-success status
+    Api {
+        method: String,
+        url: String,
+        status: u16,
+        message: String,
+        details: Vec<String>,
+    },
+    /// The request never got a response
+    Transport {
+        method: String,
+        url: String,
+        source: ureq::Error,
+    },
+}
+
+impl GitHubError {
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            GitHubError::Api { status, .. } => Some(*status),
+            GitHubError::Transport { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for GitHubError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitHubError::Api {
+                method,
+                url,
+                status,
+                message,
+                details,
+            } => {
+                write!(f, "{method} {url} returned {status}: {message}")?;
+                if !details.is_empty() {
+                    write!(f, " ({})", details.join("; "))?;
+                }
+                Ok(())
+            }
+            GitHubError::Transport { method, url, .. } => write!(f, "{method} {url} failed"),
+        }
+    }
+}
+
+impl std::error::Error for GitHubError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GitHubError::Api { .. } => None,
+            GitHubError::Transport { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Extracts `message` and the `errors` array from a GitHub error response body.
+fn parse_error_body(body: &str) -> (String, Vec<String>) {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        let trimmed = body.trim();
+        let message = if trimmed.is_empty() { "no response body" } else { trimmed };
+        return (message.to_string(), Vec::new());
+    };
+    let message = json["message"].as_str().unwrap_or("unknown error").to_string();
+    let details = json["errors"]
+        .as_array()
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|e| match e {
+                    serde_json::Value::String(s) => s.clone(),
+                    _ => {
+                        let field = [e["resource"].as_str(), e["field"].as_str()]
+                            .into_iter()
+                            .flatten()
+                            .collect::<Vec<_>>()
+                            .join(".");
+                        let what = e["code"].as_str().or(e["message"].as_str()).unwrap_or("invalid");
+                        if field.is_empty() { what.to_string() } else { format!("{field} {what}") }
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (message, details)
+}
+
+fn agent() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .build()
+            .into()
+    })
+}
+
+pub fn token() -> Result<String> {
+    std::env::var("GITHUB_TOKEN").context("GITHUB_TOKEN environment variable not set")
+}
+
+fn send(method: &str, url: &str, content_type: Option<&str>, body: Option<Vec<u8>>) -> Result<String> {
+    let token = token()?;
+    let transport = |source| GitHubError::Transport {
+        method: method.to_string(),
+        url: url.to_string(),
+        source,
+    };
+    let request = ureq::http::Request::builder()
+        .method(method)
+        .uri(url)
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", format!("Bearer {token}"))
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    let request = match content_type {
+        Some(ct) => request.header("Content-Type", ct),
+        None => request,
+    };
+    let mut response = match body {
+        Some(bytes) => agent().run(request.body(bytes)?),
+        None => agent().run(request.body(())?),
+    }
+    .map_err(transport)?;
+
+    let status = response.status().as_u16();
+    let text = response.body_mut().read_to_string().map_err(transport)?;
+    if !(200..300).contains(&status) {
+        let (message, details) = parse_error_body(&text);
+        return Err(GitHubError::Api {
+            method: method.to_string(),
+            url: url.to_string(),
+            status,
+            message,
+            details,
+        }
+        .into());
+    }
+    Ok(text)
+}
+
+/// Calls the GitHub REST API and returns the parsed JSON response (`Null` for empty bodies).
+pub fn api(label: &str, method: &str, url: &str, json_body: Option<&serde_json::Value>) -> Result<serde_json::Value> {
+    println!("[{label}] {method} {url}");
+    let body = json_body.map(|b| b.to_string().into_bytes());
+    let content_type = body.as_ref().map(|_| "application/json");
+    let text = send(method, url, content_type, body).with_context(|| format!("[{label}] API request failed"))?;
+    if text.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_str(text.trim()).with_context(|| format!("[{label}] failed to parse API response"))
+}
+
+/// Like [`api`] with GET, but returns `None` when GitHub answers 404.
+pub fn get_optional(label: &str, url: &str) -> Result<Option<serde_json::Value>> {
+    match api(label, "GET", url, None) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.downcast_ref::<GitHubError>().and_then(GitHubError::status) == Some(404) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Runs a GraphQL query, failing when GitHub reports errors (which it does with a 200 status).
+pub fn graphql(label: &str, query: &str, variables: serde_json::Value) -> Result<serde_json::Value> {
+    let body = serde_json::json!({ "query": query, "variables": variables });
+    let response = api(label, "POST", GRAPHQL_URL, Some(&body))?;
+    if let Some(errors) = response["errors"].as_array() {
+        let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+        anyhow::bail!("[{label}] GraphQL request failed: {}", messages.join("; "));
+    }
+    Ok(response["data"].clone())
+}
+
+pub fn upload_asset(label: &str, upload_url: &str, file_path: &Path, name: &str, content_type: &str) -> Result<()> {
+    println!("[{label}] Uploading {name}");
+    let data = std::fs::read(file_path).with_context(|| format!("[{label}] reading {}", file_path.display()))?;
+    let url = format!("{upload_url}?name={name}");
+    send("POST", &url, Some(content_type), Some(data)).with_context(|| format!("[{label}] upload of {name} failed"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_body_validation_failed() {
+        let body = r#"{"message":"Validation Failed","errors":[{"resource":"Release","code":"already_exists","field":"tag_name"}],"documentation_url":"https://docs.github.com"}"#;
+        let (message, details) = parse_error_body(body);
+        assert_eq!(message, "Validation Failed");
+        assert_eq!(details, vec!["Release.tag_name already_exists"]);
+    }
+
+    #[test]
+    fn parse_error_body_plain_message() {
+        let (message, details) = parse_error_body(r#"{"message":"Not Found"}"#);
+        assert_eq!(message, "Not Found");
+        assert!(details.is_empty());
+    }
+
+    #[test]
+    fn parse_error_body_not_json() {
+        assert_eq!(parse_error_body("Bad Gateway").0, "Bad Gateway");
+        assert_eq!(parse_error_body("").0, "no response body");
+    }
+
+    #[test]
+    fn api_error_display_includes_status_and_details() {
+        let err = GitHubError::Api {
+            method: "POST".to_string(),
+            url: "https://api.github.com/repos/o/r/releases".to_string(),
+            status: 422,
+            message: "Validation Failed".to_string(),
+            details: vec!["Release.tag_name already_exists".to_string()],
+        };
+        assert_eq!(err.status(), Some(422));
+        assert_eq!(
+            err.to_string(),
+            "POST https://api.github.com/repos/o/r/releases returned 422: Validation Failed (Release.tag_name already_exists)"
+        );
+    }
+}
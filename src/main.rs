@@ -1,5 +1,11 @@
+mod archive;
+mod artifact;
 mod channels;
 mod config;
+mod extras;
+mod github;
+mod package;
+mod provenance;
 
 use anyhow::{Result, bail};
 use clap::{Parser, Subcommand};
@@ -23,6 +29,12 @@ enum Command {
     Release {
         #[arg(long)]
         version: Option<String>,
+        /// Build and render every artifact under target/release-staging/dry-run without publishing
+        #[arg(long)]
+        dry_run: bool,
+        /// Continue a failed release, skipping steps recorded as completed in its journal
+        #[arg(long, conflicts_with = "dry_run")]
+        resume: bool,
         /// Channels to release to (defaults to all enabled channels)
         channels: Vec<String>,
     },
@@ -55,9 +67,9 @@ fn main() -> Result<()> {
             println!("Enabled channels: {:?}", config.enabled_channels());
             Ok(())
         }
-        Command::Release { version, channels } => {
+        Command::Release { version, dry_run, resume, channels } => {
             let channels = if channels.is_empty() { None } else { Some(channels) };
-            channels::release(&config, version.as_deref(), channels.as_deref())
+            channels::release(&config, version.as_deref(), channels.as_deref(), dry_run, resume)
         }
     }
 }
@@ -0,0 +1,228 @@
+use anyhow::{Context, Result, bail};
+use flate2::{Compression, GzBuilder};
+use md5::Md5;
+use sha2::Digest as _;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use super::{FileKind, hex};
+use crate::archive::Entry;
+use crate::config::{Config, DebPackage};
+
+/// Debian architecture for a Linux target.
+pub(super) fn deb_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("-linux-") {
+        None
+    } else if target.starts_with("x86_64") {
+        Some("amd64")
+    } else if target.starts_with("aarch64") {
+        Some("arm64")
+    } else {
+        None
+    }
+}
+
+/// A `-` would start the Debian revision, so pre-release suffixes sort with `~` instead.
+fn deb_version(version: &str) -> String {
+    version.replacen('-', "~", 1)
+}
+
+pub(super) fn file_name(package: &str, version: &str, arch: &str) -> String {
+    format!("{package}_{}_{arch}.deb", deb_version(version))
+}
+
+pub(super) fn control(config: &Config, deb: &DebPackage, version: &str, arch: &str, installed_size: u64) -> String {
+    let mut control = format!(
+        "Package: {}\nVersion: {}\nArchitecture: {arch}\nMaintainer: {}\nInstalled-Size: {installed_size}\n",
+        deb.package_name(&config.project),
+        deb_version(version),
+        deb.maintainer,
+    );
+    if !deb.depends.is_empty() {
+        control.push_str(&format!("Depends: {}\n", deb.depends.join(", ")));
+    }
+    control.push_str(&format!(
+        "Section: utils\nPriority: optional\nHomepage: https://github.com/{}\nDescription: {}\n",
+        config.project.repo,
+        deb.description.as_deref().unwrap_or(&config.project.name),
+    ));
+    control
+}
+
+/// Writes a `.deb`: an `ar` archive of `debian-binary`, `control.tar.gz` and `data.tar.gz`.
+pub(super) fn write_deb(dest: &Path, control: &str, layout: &[(Entry, FileKind)], mtime: u64) -> Result<()> {
+    let mut files = Vec::new();
+    for (entry, _) in layout {
+        let data = std::fs::read(&entry.source).with_context(|| format!("reading {}", entry.source.display()))?;
+        files.push((entry.name.as_str(), entry.mode(), data));
+    }
+    files.sort_by(|a, b| a.0.cmp(b.0));
+
+    let md5sums: String = files
+        .iter()
+        .map(|(name, _, data)| format!("{}  {name}\n", hex(&Md5::digest(data))))
+        .collect();
+    let control_tar = tar_gz(
+        &[("control", 0o644, control.as_bytes().to_vec()), ("md5sums", 0o644, md5sums.into_bytes())],
+        mtime,
+    )?;
+    let data_tar = tar_gz(&files, mtime)?;
+
+    let mut deb = b"!<arch>\n".to_vec();
+    for (name, data) in [("debian-binary", &b"2.0\n"[..]), ("control.tar.gz", &control_tar), ("data.tar.gz", &data_tar)] {
+        write_ar_member(&mut deb, name, data, mtime);
+    }
+    std::fs::write(dest, deb).with_context(|| format!("writing {}", dest.display()))
+}
+
+/// Reads the `control` file back out of a `.deb`, whichever compression its control member uses.
+pub fn read_deb_control(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let not_deb = || anyhow::anyhow!("{} is not a .deb", path.display());
+    let mut rest = bytes.strip_prefix(b"!<arch>\n").ok_or_else(not_deb)?;
+    while rest.len() >= 60 {
+        let name = String::from_utf8_lossy(&rest[..16]).trim_end().trim_end_matches('/').to_string();
+        let size: usize = String::from_utf8_lossy(&rest[48..58]).trim().parse().map_err(|_| not_deb())?;
+        let data = rest.get(60..60 + size).ok_or_else(not_deb)?;
+        if let Some(compression) = name.strip_prefix("control.tar") {
+            let reader: Box<dyn Read + '_> = match compression {
+                "" => Box::new(data),
+                ".gz" => Box::new(flate2::read::GzDecoder::new(data)),
+                ".xz" => Box::new(xz2::read::XzDecoder::new(data)),
+                ".zst" => Box::new(zstd::Decoder::new(data)?),
+                _ => bail!("{}: unsupported {name}", path.display()),
+            };
+            let mut tar = tar::Archive::new(reader);
+            for entry in tar.entries()? {
+                let mut entry = entry?;
+                if entry.path()?.as_os_str() == "control" || entry.path()?.as_os_str() == "./control" {
+                    let mut control = String::new();
+                    entry.read_to_string(&mut control)?;
+                    return Ok(control);
+                }
+            }
+            break;
+        }
+        rest = &rest[(60 + size + size % 2).min(rest.len())..];
+    }
+    bail!("{} has no control file", path.display())
+}
+
+fn write_ar_member(out: &mut Vec<u8>, name: &str, data: &[u8], mtime: u64) {
+    out.extend(format!("{name:<16}{mtime:<12}{:<6}{:<6}{:<8o}{:<10}`\n", 0, 0, 0o100644, data.len()).as_bytes());
+    out.extend(data);
+    if data.len() % 2 == 1 {
+        out.push(b'\n');
+    }
+}
+
+/// A gzipped tar of `files` under a `./` root entry, with an entry for every parent directory.
+fn tar_gz<N: AsRef<str>>(files: &[(N, u32, Vec<u8>)], mtime: u64) -> Result<Vec<u8>> {
+    let mut dirs = std::collections::BTreeSet::new();
+    for (name, ..) in files {
+        let mut path = name.as_ref();
+        while let Some((parent, _)) = path.rsplit_once('/') {
+            dirs.insert(parent.to_string());
+            path = parent;
+        }
+    }
+
+    let gz = GzBuilder::new().mtime(0).write(Vec::new(), Compression::best());
+    let mut builder = tar::Builder::new(gz);
+    let header = |entry_type, size, mode| -> Result<tar::Header> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_size(size);
+        header.set_mode(mode);
+        header.set_mtime(mtime);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_username("root")?;
+        header.set_groupname("root")?;
+        Ok(header)
+    };
+    let mut root = header(tar::EntryType::Directory, 0, 0o755)?;
+    builder.append_data(&mut root, "./", std::io::empty())?;
+    for dir in dirs {
+        let mut h = header(tar::EntryType::Directory, 0, 0o755)?;
+        builder.append_data(&mut h, format!("{dir}/"), std::io::empty())?;
+    }
+    for (name, mode, data) in files {
+        let mut h = header(tar::EntryType::Regular, data.len() as u64, *mode)?;
+        builder.append_data(&mut h, name.as_ref(), &data[..])?;
+    }
+    let mut gz = builder.into_inner()?;
+    gz.flush()?;
+    Ok(gz.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::{test_dir, test_entry};
+
+    // --- deb tests ---
+
+    #[test]
+    fn deb_arch_maps_linux_targets() {
+        assert_eq!(deb_arch("x86_64-unknown-linux-gnu"), Some("amd64"));
+        assert_eq!(deb_arch("aarch64-unknown-linux-musl"), Some("arm64"));
+        assert_eq!(deb_arch("x86_64-apple-darwin"), None);
+        assert_eq!(deb_arch("armv7-unknown-linux-gnueabihf"), None);
+    }
+
+    #[test]
+    fn deb_version_turns_prerelease_into_tilde() {
+        assert_eq!(deb_version("1.2.3"), "1.2.3");
+        assert_eq!(deb_version("1.2.3-rc.1"), "1.2.3~rc.1");
+        assert_eq!(file_name("tool", "1.2.3-rc.1", "arm64"), "tool_1.2.3~rc.1_arm64.deb");
+    }
+
+    #[test]
+    fn write_ar_member_pads_to_even_length() {
+        let mut out = Vec::new();
+        write_ar_member(&mut out, "debian-binary", b"2.0\n", 7);
+        assert_eq!(&out[..60], b"debian-binary   7           0     0     100644  4         `\n");
+        assert_eq!(out.len(), 64);
+        write_ar_member(&mut out, "odd", b"abc", 7);
+        assert_eq!(out.len(), 64 + 60 + 4);
+    }
+
+    #[test]
+    fn write_deb_is_reproducible_and_readable() {
+        let dir = test_dir("deb");
+        let layout = vec![(test_entry(&dir, "usr/bin/tool", "#!/bin/sh\necho hi\n", true), FileKind::Program)];
+        let control = "Package: tool\nVersion: 1.0.0\nArchitecture: amd64\nMaintainer: Me <me@example.com>\nDescription: A tool\n";
+        let (a, b) = (dir.join("a.deb"), dir.join("b.deb"));
+        write_deb(&a, control, &layout, 1_700_000_000).unwrap();
+        write_deb(&b, control, &layout, 1_700_000_000).unwrap();
+        let bytes = std::fs::read(&a).unwrap();
+        assert_eq!(bytes, std::fs::read(&b).unwrap());
+        assert!(bytes.starts_with(b"!<arch>\ndebian-binary   "));
+
+        // the data member unpacks to the installed tree
+        let data_start = bytes.windows(16).position(|w| w == b"data.tar.gz     ").unwrap() + 60;
+        let size: usize = String::from_utf8_lossy(&bytes[data_start - 12..data_start - 2]).trim().parse().unwrap();
+        let gz = flate2::read::GzDecoder::new(&bytes[data_start..data_start + size]);
+        let mut tar = tar::Archive::new(gz);
+        let entries: Vec<(String, u32)> = tar
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.path().unwrap().to_string_lossy().into_owned(), e.header().mode().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("./".to_string(), 0o755),
+                ("usr/".to_string(), 0o755),
+                ("usr/bin/".to_string(), 0o755),
+                ("usr/bin/tool".to_string(), 0o755),
+            ]
+        );
+        assert_eq!(read_deb_control(&a).unwrap(), control);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,271 @@
+//! Native Linux packages, written in-process as reproducibly as the archives.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::archive::Entry;
+use crate::artifact::{Digests, digest_file};
+use crate::config::Config;
+use crate::extras::{Shell, completions, man_pages};
+
+mod deb;
+mod rpm;
+
+pub use deb::read_deb_control;
+pub use rpm::{Dependency, RpmInfo, read_rpm};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageFormat {
+    Deb,
+    Rpm,
+}
+
+impl PackageFormat {
+    /// Content type used when uploading the package as a release asset
+    pub fn content_type(self) -> &'static str {
+        match self {
+            PackageFormat::Deb => "application/vnd.debian.binary-package",
+            PackageFormat::Rpm => "application/x-rpm",
+        }
+    }
+}
+
+/// A built package for one target, uploaded next to the archives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Package {
+    pub target: String,
+    pub format: PackageFormat,
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub digests: Digests,
+}
+
+impl Package {
+    /// File name of the package, which is also its release asset name.
+    pub fn name(&self) -> String {
+        self.path.file_name().unwrap_or_default().to_string_lossy().into_owned()
+    }
+}
+
+/// What an installed file is, as far as package metadata cares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileKind {
+    Program,
+    Data,
+    License,
+    Doc,
+}
+
+fn is_license(file: &str) -> bool {
+    file.starts_with("LICENSE") || file.starts_with("COPYING")
+}
+
+fn is_doc(file: &str) -> bool {
+    ["README", "CHANGELOG", "CHANGES", "NEWS"].iter().any(|p| file.starts_with(p))
+}
+
+/// Where every binary and recognised extra file is installed, as entries named by their
+/// path below `/`. Debian keeps licenses with the docs and leaves other docs out; RPM
+/// distributions give licenses their own directory.
+fn install_layout(format: PackageFormat, package: &str, binaries: &[Entry], includes: &[Entry]) -> Vec<(Entry, FileKind)> {
+    let mut layout: Vec<(Entry, FileKind)> = binaries
+        .iter()
+        .map(|b| {
+            let entry = Entry {
+                name: format!("usr/bin/{}", b.name),
+                source: b.source.clone(),
+                executable: true,
+            };
+            (entry, FileKind::Program)
+        })
+        .collect();
+    let files: Vec<String> = includes.iter().map(|e| e.name.clone()).collect();
+    let source = |path: &str| includes.iter().find(|e| e.name == path).map(|e| e.source.clone()).unwrap();
+    let installed = |name: String, path: &str, kind| {
+        let entry = Entry {
+            name,
+            source: source(path),
+            executable: false,
+        };
+        (entry, kind)
+    };
+    for (shell, path, command) in completions(&files) {
+        let name = match (shell, format) {
+            (Shell::Bash, _) => format!("usr/share/bash-completion/completions/{command}"),
+            (Shell::Zsh, PackageFormat::Deb) => format!("usr/share/zsh/vendor-completions/_{command}"),
+            (Shell::Zsh, PackageFormat::Rpm) => format!("usr/share/zsh/site-functions/_{command}"),
+            (Shell::Fish, _) => format!("usr/share/fish/vendor_completions.d/{command}.fish"),
+        };
+        layout.push(installed(name, path, FileKind::Data));
+    }
+    for (section, path) in man_pages(&files) {
+        let file = path.rsplit('/').next().unwrap_or(path);
+        layout.push(installed(format!("usr/share/man/man{section}/{file}"), path, FileKind::Doc));
+    }
+    for path in &files {
+        let file = path.rsplit('/').next().unwrap_or(path);
+        let entry = match format {
+            PackageFormat::Deb if is_license(file) => (format!("usr/share/doc/{package}/{file}"), FileKind::License),
+            PackageFormat::Rpm if is_license(file) => (format!("usr/share/licenses/{package}/{file}"), FileKind::License),
+            PackageFormat::Rpm if is_doc(file) => (format!("usr/share/doc/{package}/{file}"), FileKind::Doc),
+            _ => continue,
+        };
+        layout.push(installed(entry.0, path, entry.1));
+    }
+    layout
+}
+
+fn installed_size(layout: &[(Entry, FileKind)]) -> Result<u64> {
+    let mut size = 0;
+    for (entry, _) in layout {
+        size += std::fs::metadata(&entry.source)
+            .with_context(|| format!("reading {}", entry.source.display()))?
+            .len();
+    }
+    Ok(size)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Builds the packages configured under `[packages]` for one target from its binaries
+/// (named by their installed file name) and `[build] include` files.
+pub fn build_packages(
+    config: &Config,
+    version: &str,
+    target: &str,
+    binaries: &[Entry],
+    includes: &[Entry],
+    staging: &Path,
+    mtime: u64,
+) -> Result<Vec<Package>> {
+    let mut built = Vec::new();
+    if let Some(pkg) = config.packages.deb.as_ref().filter(|d| d.enabled)
+        && let Some(arch) = deb::deb_arch(target)
+    {
+        let name = pkg.package_name(&config.project);
+        let layout = install_layout(PackageFormat::Deb, &name, binaries, includes);
+        let control = deb::control(config, pkg, version, arch, installed_size(&layout)?.div_ceil(1024));
+        let path = staging.join(deb::file_name(&name, version, arch));
+        println!("[build] Packing {}", path.display());
+        deb::write_deb(&path, &control, &layout, mtime)?;
+        built.push((PackageFormat::Deb, path));
+    }
+    if let Some(pkg) = config.packages.rpm.as_ref().filter(|r| r.enabled)
+        && let Some(arch) = rpm::rpm_arch(target)
+    {
+        let name = pkg.package_name(&config.project);
+        let layout = install_layout(PackageFormat::Rpm, &name, binaries, includes);
+        let metadata = rpm::Metadata::new(config, pkg, version, arch, mtime);
+        let path = staging.join(metadata.file_name());
+        println!("[build] Packing {}", path.display());
+        rpm::write_rpm(&path, &metadata, &layout)?;
+        built.push((PackageFormat::Rpm, path));
+    }
+
+    built
+        .into_iter()
+        .map(|(format, path)| {
+            Ok(Package {
+                target: target.to_string(),
+                format,
+                digests: digest_file(&path, &config.build.digests)?,
+                path,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn test_entry(dir: &Path, name: &str, content: &str, executable: bool) -> Entry {
+    let source = dir.join(name.replace('/', "_"));
+    std::fs::write(&source, content).unwrap();
+    Entry {
+        name: name.to_string(),
+        source,
+        executable,
+    }
+}
+
+#[cfg(test)]
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("releasor2000-package-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Builds a `.deb` and an `.rpm` of a one-binary project into `dir`.
+#[cfg(test)]
+pub(crate) fn test_packages(dir: &Path, version: &str) -> Vec<Package> {
+    let config = Config::parse(
+        r#"
+[project]
+name = "tool"
+repo = "owner/tool"
+
+[build]
+pre_built_dir = "dist"
+targets = ["x86_64-unknown-linux-gnu"]
+
+[packages.deb]
+maintainer = "Me <me@example.com>"
+
+[packages.rpm]
+license = "MIT"
+"#,
+    )
+    .unwrap();
+    let binaries = vec![test_entry(dir, "tool", "#!/bin/sh\n", true)];
+    build_packages(&config, version, "x86_64-unknown-linux-gnu", &binaries, &[], dir, 1_700_000_000).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- install_layout tests ---
+
+    fn layout(format: PackageFormat) -> Vec<(String, FileKind)> {
+        let dir = test_dir(&format!("layout-{format:?}"));
+        let binaries = vec![test_entry(&dir, "tool", "bin", true)];
+        let includes = vec![
+            test_entry(&dir, "LICENSE", "mit", false),
+            test_entry(&dir, "README.md", "readme", false),
+            test_entry(&dir, "completions/_tool", "zsh", false),
+            test_entry(&dir, "man/tool.1", "man", false),
+        ];
+        let layout = install_layout(format, "tool", &binaries, &includes);
+        std::fs::remove_dir_all(&dir).ok();
+        layout.into_iter().map(|(e, kind)| (e.name, kind)).collect()
+    }
+
+    #[test]
+    fn install_layout_places_files_in_debian_directories() {
+        assert_eq!(
+            layout(PackageFormat::Deb),
+            vec![
+                ("usr/bin/tool".to_string(), FileKind::Program),
+                ("usr/share/zsh/vendor-completions/_tool".to_string(), FileKind::Data),
+                ("usr/share/man/man1/tool.1".to_string(), FileKind::Doc),
+                ("usr/share/doc/tool/LICENSE".to_string(), FileKind::License),
+            ]
+        );
+    }
+
+    #[test]
+    fn install_layout_places_files_in_rpm_directories() {
+        assert_eq!(
+            layout(PackageFormat::Rpm),
+            vec![
+                ("usr/bin/tool".to_string(), FileKind::Program),
+                ("usr/share/zsh/site-functions/_tool".to_string(), FileKind::Data),
+                ("usr/share/man/man1/tool.1".to_string(), FileKind::Doc),
+                ("usr/share/licenses/tool/LICENSE".to_string(), FileKind::License),
+                ("usr/share/doc/tool/README.md".to_string(), FileKind::Doc),
+            ]
+        );
+    }
+}
@@ -0,0 +1,641 @@
+use anyhow::{Context, Result};
+use flate2::{Compression, GzBuilder};
+use md5::Md5;
+use sha2::{Digest as _, Sha256};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use super::{FileKind, hex};
+use crate::archive::Entry;
+use crate::config::{Config, RpmPackage};
+
+const RPMSENSE_LESS: u32 = 1 << 1;
+const RPMSENSE_GREATER: u32 = 1 << 2;
+const RPMSENSE_EQUAL: u32 = 1 << 3;
+const RPMSENSE_RPMLIB: u32 = 1 << 24;
+
+const RPMFILE_DOC: u32 = 1 << 1;
+const RPMFILE_LICENSE: u32 = 1 << 7;
+
+/// `PGPHASHALGO_SHA256`, for file and payload digests
+const SHA256_ALGO: u32 = 8;
+
+/// RPM architecture and lead architecture number for a Linux target.
+pub(super) fn rpm_arch(target: &str) -> Option<&'static str> {
+    if !target.contains("-linux-") {
+        None
+    } else if target.starts_with("x86_64") {
+        Some("x86_64")
+    } else if target.starts_with("aarch64") {
+        Some("aarch64")
+    } else {
+        None
+    }
+}
+
+/// RPM versions may not contain `-`; `~` sorts pre-releases before the release.
+fn rpm_version(version: &str) -> String {
+    version.replace('-', "~")
+}
+
+/// Parses `name`, or `name <op> version` with `<`, `<=`, `=`, `>=` or `>`.
+fn parse_dependency(spec: &str) -> (String, u32, String) {
+    let parts: Vec<&str> = spec.split_whitespace().collect();
+    match parts.as_slice() {
+        [name, op, version] => {
+            let flags = match *op {
+                "<" => RPMSENSE_LESS,
+                "<=" => RPMSENSE_LESS | RPMSENSE_EQUAL,
+                "=" | "==" => RPMSENSE_EQUAL,
+                ">=" => RPMSENSE_GREATER | RPMSENSE_EQUAL,
+                ">" => RPMSENSE_GREATER,
+                _ => return (spec.trim().to_string(), 0, String::new()),
+            };
+            (name.to_string(), flags, version.to_string())
+        }
+        _ => (spec.trim().to_string(), 0, String::new()),
+    }
+}
+
+/// Package-level metadata written to the main header.
+pub(super) struct Metadata {
+    name: String,
+    version: String,
+    release: String,
+    arch: &'static str,
+    summary: String,
+    description: String,
+    license: String,
+    url: String,
+    requires: Vec<(String, u32, String)>,
+    build_time: u32,
+}
+
+impl Metadata {
+    pub(super) fn new(config: &Config, rpm: &RpmPackage, version: &str, arch: &'static str, mtime: u64) -> Self {
+        let summary = rpm.summary.clone().unwrap_or_else(|| config.project.name.clone());
+        Self {
+            name: rpm.package_name(&config.project),
+            version: rpm_version(version),
+            release: rpm.release.clone(),
+            arch,
+            description: rpm.description.clone().unwrap_or_else(|| summary.clone()),
+            summary,
+            license: rpm.license.clone().unwrap_or_else(|| "Unspecified".to_string()),
+            url: format!("https://github.com/{}", config.project.repo),
+            requires: rpm.requires.iter().map(|r| parse_dependency(r)).collect(),
+            build_time: mtime as u32,
+        }
+    }
+
+    pub(super) fn file_name(&self) -> String {
+        format!("{}-{}-{}.{}.rpm", self.name, self.version, self.release, self.arch)
+    }
+}
+
+/// Typed header values, stored big-endian.
+enum Value {
+    Int16(Vec<u16>),
+    Int32(Vec<u32>),
+    Str(String),
+    Bin(Vec<u8>),
+    StrArray(Vec<String>),
+    I18nStr(String),
+}
+
+impl Value {
+    fn type_code(&self) -> u32 {
+        match self {
+            Value::Int16(_) => 3,
+            Value::Int32(_) => 4,
+            Value::Str(_) => 6,
+            Value::Bin(_) => 7,
+            Value::StrArray(_) => 8,
+            Value::I18nStr(_) => 9,
+        }
+    }
+
+    fn count(&self) -> u32 {
+        match self {
+            Value::Int16(v) => v.len() as u32,
+            Value::Int32(v) => v.len() as u32,
+            Value::Bin(v) => v.len() as u32,
+            Value::StrArray(v) => v.len() as u32,
+            Value::Str(_) | Value::I18nStr(_) => 1,
+        }
+    }
+
+    fn alignment(&self) -> usize {
+        match self {
+            Value::Int16(_) => 2,
+            Value::Int32(_) => 4,
+            _ => 1,
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        let mut string = |s: &str| {
+            out.extend(s.as_bytes());
+            out.push(0);
+        };
+        match self {
+            Value::Str(s) | Value::I18nStr(s) => string(s),
+            Value::StrArray(v) => v.iter().for_each(|s| string(s)),
+            Value::Int16(v) => v.iter().for_each(|n| out.extend(n.to_be_bytes())),
+            Value::Int32(v) => v.iter().for_each(|n| out.extend(n.to_be_bytes())),
+            Value::Bin(v) => out.extend(v),
+        }
+    }
+
+    /// Decodes `count` values of `type_code` from the start of `data`. Types this module
+    /// never writes are skipped.
+    fn read(type_code: u32, count: usize, data: &[u8]) -> Option<Value> {
+        let strings = |count| {
+            let mut strings = Vec::new();
+            let mut rest = data;
+            for _ in 0..count {
+                let end = rest.iter().position(|b| *b == 0)?;
+                strings.push(String::from_utf8_lossy(&rest[..end]).into_owned());
+                rest = &rest[end + 1..];
+            }
+            Some(strings)
+        };
+        let value = match type_code {
+            3 => Value::Int16(data.get(..count * 2)?.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect()),
+            4 => Value::Int32(data.get(..count * 4)?.chunks(4).map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]])).collect()),
+            6 => Value::Str(strings(1)?.remove(0)),
+            7 => Value::Bin(data.get(..count)?.to_vec()),
+            8 => Value::StrArray(strings(count)?),
+            // the first translation is the `C` locale
+            9 => Value::I18nStr(strings(1)?.remove(0)),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Encodes a header whose tags all sit in one immutable region named by `region_tag`.
+fn encode_header(region_tag: u32, mut tags: Vec<(u32, Value)>) -> Vec<u8> {
+    tags.sort_by_key(|(tag, _)| *tag);
+    let count = tags.len() as u32 + 1;
+
+    let mut index = Vec::new();
+    let mut store = Vec::new();
+    for (tag, value) in &tags {
+        while !store.len().is_multiple_of(value.alignment()) {
+            store.push(0);
+        }
+        for n in [*tag, value.type_code(), store.len() as u32, value.count()] {
+            index.extend(n.to_be_bytes());
+        }
+        value.write(&mut store);
+    }
+    // the region trailer points back over every index entry
+    let trailer_offset = store.len() as u32;
+    for n in [region_tag, 7, (-(count as i32 * 16)) as u32, 16] {
+        store.extend(n.to_be_bytes());
+    }
+
+    let mut out = vec![0x8e, 0xad, 0xe8, 0x01, 0, 0, 0, 0];
+    out.extend(count.to_be_bytes());
+    out.extend((store.len() as u32).to_be_bytes());
+    for n in [region_tag, 7, trailer_offset, 16] {
+        out.extend(n.to_be_bytes());
+    }
+    out.extend(index);
+    out.extend(store);
+    out
+}
+
+/// Decodes the header at the start of `bytes` into its tags and its length in bytes.
+fn decode_header(bytes: &[u8]) -> Option<(BTreeMap<u32, Value>, usize)> {
+    if bytes.get(..4)? != [0x8e, 0xad, 0xe8, 0x01] {
+        return None;
+    }
+    let be32 = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    let (count, size) = (be32(8)? as usize, be32(12)? as usize);
+    let store = bytes.get(16 + count * 16..16 + count * 16 + size)?;
+    let mut tags = BTreeMap::new();
+    for entry in (16..16 + count * 16).step_by(16) {
+        let (tag, type_code, offset, n) = (be32(entry)?, be32(entry + 4)?, be32(entry + 8)?, be32(entry + 12)?);
+        if let Some(value) = Value::read(type_code, n as usize, store.get(offset as usize..)?) {
+            tags.insert(tag, value);
+        }
+    }
+    Some((tags, 16 + count * 16 + size))
+}
+
+fn lead(metadata: &Metadata) -> Vec<u8> {
+    let mut lead = vec![0xed, 0xab, 0xee, 0xdb, 3, 0];
+    lead.extend(0u16.to_be_bytes()); // binary package
+    let archnum: u16 = if metadata.arch == "aarch64" { 19 } else { 1 };
+    lead.extend(archnum.to_be_bytes());
+    let mut name = format!("{}-{}-{}", metadata.name, metadata.version, metadata.release).into_bytes();
+    name.resize(66, 0);
+    name[65] = 0;
+    lead.extend(name);
+    lead.extend(1u16.to_be_bytes()); // linux
+    lead.extend(5u16.to_be_bytes()); // header-style signature
+    lead.extend([0; 16]);
+    lead
+}
+
+fn cpio_entry(out: &mut Vec<u8>, name: &str, ino: u32, mode: u32, mtime: u32, data: &[u8]) {
+    let fields = [ino, mode, 0, 0, 1, mtime, data.len() as u32, 0, 0, 0, 0, name.len() as u32 + 1, 0];
+    out.extend(b"070701");
+    for field in fields {
+        out.extend(format!("{field:08x}").as_bytes());
+    }
+    out.extend(name.as_bytes());
+    out.push(0);
+    while !out.len().is_multiple_of(4) {
+        out.push(0);
+    }
+    out.extend(data);
+    while !out.len().is_multiple_of(4) {
+        out.push(0);
+    }
+}
+
+/// Writes an RPM installing every entry of `layout` at `/<name>`, owned by root.
+pub(super) fn write_rpm(dest: &Path, metadata: &Metadata, layout: &[(Entry, FileKind)]) -> Result<()> {
+    let mut files = Vec::new();
+    for (entry, kind) in layout {
+        let data = std::fs::read(&entry.source).with_context(|| format!("reading {}", entry.source.display()))?;
+        files.push((format!("/{}", entry.name), 0o100000 | entry.mode(), *kind, data));
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mtime = metadata.build_time;
+    let mut cpio = Vec::new();
+    for (i, (path, mode, _, data)) in files.iter().enumerate() {
+        cpio_entry(&mut cpio, &format!(".{path}"), i as u32 + 1, *mode, mtime, data);
+    }
+    cpio_entry(&mut cpio, "TRAILER!!!", 0, 0, 0, &[]);
+    let mut gz = GzBuilder::new().mtime(0).write(Vec::new(), Compression::best());
+    gz.write_all(&cpio)?;
+    let payload = gz.finish()?;
+
+    let mut dirnames: Vec<String> = Vec::new();
+    let mut dirindexes = Vec::new();
+    let mut basenames = Vec::new();
+    for (path, ..) in &files {
+        let (dir, base) = path.rsplit_once('/').expect("installed paths are absolute");
+        let dir = format!("{dir}/");
+        let index = dirnames.iter().position(|d| *d == dir).unwrap_or_else(|| {
+            dirnames.push(dir);
+            dirnames.len() - 1
+        });
+        dirindexes.push(index as u32);
+        basenames.push(base.to_string());
+    }
+
+    let n = files.len();
+    let evr = format!("{}-{}", metadata.version, metadata.release);
+    let arch_provide = match metadata.arch {
+        "x86_64" => format!("{}(x86-64)", metadata.name),
+        _ => format!("{}(aarch-64)", metadata.name),
+    };
+    let rpmlib = |feature: &str, version: &str| {
+        (format!("rpmlib({feature})"), RPMSENSE_RPMLIB | RPMSENSE_LESS | RPMSENSE_EQUAL, version.to_string())
+    };
+    let mut requires = metadata.requires.clone();
+    requires.extend([
+        rpmlib("CompressedFileNames", "3.0.4-1"),
+        rpmlib("FileDigests", "4.6.0-1"),
+        rpmlib("PayloadFilesHavePrefix", "4.0-1"),
+    ]);
+
+    let strings = |f: &dyn Fn(usize) -> String| Value::StrArray((0..n).map(f).collect());
+    let header = encode_header(
+        63,
+        vec![
+            (100, Value::StrArray(vec!["C".to_string()])),
+            (1000, Value::Str(metadata.name.clone())),
+            (1001, Value::Str(metadata.version.clone())),
+            (1002, Value::Str(metadata.release.clone())),
+            (1004, Value::I18nStr(metadata.summary.clone())),
+            (1005, Value::I18nStr(metadata.description.clone())),
+            (1006, Value::Int32(vec![mtime])),
+            (1007, Value::Str("localhost".to_string())),
+            (1009, Value::Int32(vec![files.iter().map(|f| f.3.len() as u32).sum()])),
+            (1014, Value::Str(metadata.license.clone())),
+            (1016, Value::I18nStr("Unspecified".to_string())),
+            (1020, Value::Str(metadata.url.clone())),
+            (1021, Value::Str("linux".to_string())),
+            (1022, Value::Str(metadata.arch.to_string())),
+            (1028, Value::Int32(files.iter().map(|f| f.3.len() as u32).collect())),
+            (1030, Value::Int16(files.iter().map(|f| f.1 as u16).collect())),
+            (1033, Value::Int16(vec![0; n])),
+            (1034, Value::Int32(vec![mtime; n])),
+            (1035, Value::StrArray(files.iter().map(|f| hex(&Sha256::digest(&f.3))).collect())),
+            (1036, strings(&|_| String::new())),
+            (
+                1037,
+                Value::Int32(
+                    files
+                        .iter()
+                        .map(|f| match f.2 {
+                            FileKind::License => RPMFILE_LICENSE,
+                            FileKind::Doc => RPMFILE_DOC,
+                            FileKind::Program | FileKind::Data => 0,
+                        })
+                        .collect(),
+                ),
+            ),
+            (1039, strings(&|_| "root".to_string())),
+            (1040, strings(&|_| "root".to_string())),
+            (1044, Value::Str(format!("{}-{evr}.src.rpm", metadata.name))),
+            (1045, Value::Int32(vec![u32::MAX; n])),
+            (1047, Value::StrArray(vec![metadata.name.clone(), arch_provide])),
+            (1048, Value::Int32(requires.iter().map(|r| r.1).collect())),
+            (1049, Value::StrArray(requires.iter().map(|r| r.0.clone()).collect())),
+            (1050, Value::StrArray(requires.iter().map(|r| r.2.clone()).collect())),
+            (1064, Value::Str("4.16.0".to_string())),
+            (1095, Value::Int32(vec![1; n])),
+            (1096, Value::Int32((1..=n as u32).collect())),
+            (1097, strings(&|_| String::new())),
+            (1112, Value::Int32(vec![RPMSENSE_EQUAL; 2])),
+            (1113, Value::StrArray(vec![evr.clone(), evr])),
+            (1116, Value::Int32(dirindexes)),
+            (1117, Value::StrArray(basenames)),
+            (1118, Value::StrArray(dirnames)),
+            (1124, Value::Str("cpio".to_string())),
+            (1125, Value::Str("gzip".to_string())),
+            (1126, Value::Str("9".to_string())),
+            (5011, Value::Int32(vec![SHA256_ALGO])),
+            (5092, Value::StrArray(vec![hex(&Sha256::digest(&payload))])),
+            (5093, Value::Int32(vec![SHA256_ALGO])),
+        ],
+    );
+
+    let mut md5 = Md5::new();
+    md5.update(&header);
+    md5.update(&payload);
+    let mut signature = encode_header(
+        62,
+        vec![
+            (273, Value::Str(hex(&Sha256::digest(&header)))),
+            (1000, Value::Int32(vec![(header.len() + payload.len()) as u32])),
+            (1004, Value::Bin(md5.finalize().to_vec())),
+            (1007, Value::Int32(vec![cpio.len() as u32])),
+        ],
+    );
+    // the main header starts on an 8-byte boundary
+    while !signature.len().is_multiple_of(8) {
+        signature.push(0);
+    }
+
+    let mut rpm = lead(metadata);
+    rpm.extend(signature);
+    rpm.extend(header);
+    rpm.extend(payload);
+    std::fs::write(dest, rpm).with_context(|| format!("writing {}", dest.display()))
+}
+
+/// A dependency as repository metadata lists it, with flags such as `GE`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub name: String,
+    pub flags: Option<&'static str>,
+    pub version: String,
+}
+
+fn flag_name(flags: u32) -> Option<&'static str> {
+    let flags = flags & (RPMSENSE_LESS | RPMSENSE_GREATER | RPMSENSE_EQUAL);
+    [
+        (RPMSENSE_LESS, "LT"),
+        (RPMSENSE_LESS | RPMSENSE_EQUAL, "LE"),
+        (RPMSENSE_EQUAL, "EQ"),
+        (RPMSENSE_GREATER | RPMSENSE_EQUAL, "GE"),
+        (RPMSENSE_GREATER, "GT"),
+    ]
+    .into_iter()
+    .find(|(f, _)| *f == flags)
+    .map(|(_, name)| name)
+}
+
+/// What yum repository metadata lists about an `.rpm`, read back from its headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpmInfo {
+    pub name: String,
+    pub epoch: u32,
+    pub version: String,
+    pub release: String,
+    pub arch: String,
+    pub summary: String,
+    pub description: String,
+    pub packager: String,
+    pub url: String,
+    pub license: String,
+    pub vendor: String,
+    pub group: String,
+    pub build_host: String,
+    pub source_rpm: String,
+    pub build_time: u32,
+    pub installed_size: u64,
+    /// Size of the uncompressed payload
+    pub archive_size: u64,
+    /// Byte range of the main header within the file
+    pub header_range: (usize, usize),
+    pub provides: Vec<Dependency>,
+    /// Without the `rpmlib()` requirements, which only rpm itself resolves
+    pub requires: Vec<Dependency>,
+    /// Installed paths, and whether each is a directory
+    pub files: Vec<(String, bool)>,
+}
+
+/// Reads the metadata of an `.rpm` from its signature and main headers.
+pub fn read_rpm(path: &Path) -> Result<RpmInfo> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let not_rpm = || anyhow::anyhow!("{} is not an .rpm", path.display());
+    if !bytes.starts_with(&[0xed, 0xab, 0xee, 0xdb]) {
+        return Err(not_rpm());
+    }
+    let (signature, signature_len) = bytes.get(96..).and_then(decode_header).ok_or_else(not_rpm)?;
+    let start = 96 + signature_len.next_multiple_of(8);
+    let (header, header_len) = bytes.get(start..).and_then(decode_header).ok_or_else(not_rpm)?;
+
+    let strings = |tag| match header.get(&tag) {
+        Some(Value::Str(s) | Value::I18nStr(s)) => vec![s.clone()],
+        Some(Value::StrArray(v)) => v.clone(),
+        _ => Vec::new(),
+    };
+    let string = |tag| strings(tag).into_iter().next().unwrap_or_default();
+    let ints = |tags: &BTreeMap<u32, Value>, tag| match tags.get(&tag) {
+        Some(Value::Int32(v)) => v.clone(),
+        Some(Value::Int16(v)) => v.iter().map(|n| *n as u32).collect(),
+        _ => Vec::new(),
+    };
+    let int = |tags, tag| ints(tags, tag).first().copied().unwrap_or(0);
+    let dependencies = |names, flags, versions| -> Vec<Dependency> {
+        let flags = ints(&header, flags);
+        strings(names)
+            .into_iter()
+            .zip(strings(versions))
+            .zip(flags)
+            .filter(|((name, _), flags)| flags & RPMSENSE_RPMLIB == 0 && !name.starts_with("rpmlib("))
+            .map(|((name, version), flags)| Dependency {
+                name,
+                flags: flag_name(flags),
+                version,
+            })
+            .collect()
+    };
+    let dirnames = strings(1118);
+    let modes = ints(&header, 1030);
+    let files = ints(&header, 1116)
+        .into_iter()
+        .zip(strings(1117))
+        .enumerate()
+        .map(|(i, (dir, base))| {
+            let dir = dirnames.get(dir as usize).map(String::as_str).unwrap_or_default();
+            (format!("{dir}{base}"), modes.get(i).is_some_and(|m| m & 0o170000 == 0o040000))
+        })
+        .collect();
+
+    Ok(RpmInfo {
+        name: string(1000),
+        epoch: int(&header, 1003),
+        version: string(1001),
+        release: string(1002),
+        arch: string(1022),
+        summary: string(1004),
+        description: string(1005),
+        packager: string(1015),
+        url: string(1020),
+        license: string(1014),
+        vendor: string(1011),
+        group: string(1016),
+        build_host: string(1007),
+        source_rpm: string(1044),
+        build_time: int(&header, 1006),
+        installed_size: int(&header, 1009) as u64,
+        archive_size: int(&signature, 1007) as u64,
+        header_range: (start, start + header_len),
+        provides: dependencies(1047, 1112, 1113),
+        requires: dependencies(1049, 1048, 1050),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::{test_dir, test_entry};
+
+    fn metadata() -> Metadata {
+        Metadata {
+            name: "tool".to_string(),
+            version: rpm_version("1.2.3-rc.1"),
+            release: "1".to_string(),
+            arch: "x86_64",
+            summary: "A tool".to_string(),
+            description: "A tool".to_string(),
+            license: "MIT".to_string(),
+            url: "https://github.com/owner/tool".to_string(),
+            requires: vec![parse_dependency("glibc >= 2.28")],
+            build_time: 1_700_000_000,
+        }
+    }
+
+    fn be32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    // --- rpm tests ---
+
+    #[test]
+    fn rpm_arch_and_version() {
+        assert_eq!(rpm_arch("x86_64-unknown-linux-musl"), Some("x86_64"));
+        assert_eq!(rpm_arch("aarch64-unknown-linux-gnu"), Some("aarch64"));
+        assert_eq!(rpm_arch("x86_64-pc-windows-msvc"), None);
+        assert_eq!(rpm_version("1.2.3-rc.1"), "1.2.3~rc.1");
+        assert_eq!(metadata().file_name(), "tool-1.2.3~rc.1-1.x86_64.rpm");
+    }
+
+    #[test]
+    fn parse_dependency_reads_version_constraints() {
+        assert_eq!(parse_dependency("glibc"), ("glibc".to_string(), 0, String::new()));
+        assert_eq!(
+            parse_dependency("openssl-libs >= 3.0"),
+            ("openssl-libs".to_string(), RPMSENSE_GREATER | RPMSENSE_EQUAL, "3.0".to_string())
+        );
+        assert_eq!(parse_dependency("zlib < 2"), ("zlib".to_string(), RPMSENSE_LESS, "2".to_string()));
+    }
+
+    #[test]
+    fn encode_header_wraps_tags_in_region() {
+        let header = encode_header(63, vec![(1000, Value::Str("tool".to_string())), (1006, Value::Int32(vec![7]))]);
+        assert_eq!(&header[..8], &[0x8e, 0xad, 0xe8, 0x01, 0, 0, 0, 0]);
+        assert_eq!(be32(&header, 8), 3);
+        let data_len = be32(&header, 12) as usize;
+        assert_eq!(header.len(), 16 + 3 * 16 + data_len);
+
+        // region entry first, pointing at the trailer at the end of the data store
+        assert_eq!((be32(&header, 16), be32(&header, 20), be32(&header, 28)), (63, 7, 16));
+        let store = &header[16 + 48..];
+        let trailer = be32(&header, 24) as usize;
+        assert_eq!(trailer + 16, data_len);
+        assert_eq!(be32(store, trailer), 63);
+        assert_eq!(be32(store, trailer + 8) as i32, -48);
+
+        // the int32 is aligned after "tool\0"
+        assert_eq!((be32(&header, 32), be32(&header, 36), be32(&header, 40)), (1000, 6, 0));
+        assert_eq!((be32(&header, 48), be32(&header, 52), be32(&header, 56)), (1006, 4, 8));
+        assert_eq!(&store[..5], b"tool\0");
+        assert_eq!(be32(store, 8), 7);
+    }
+
+    #[test]
+    fn write_rpm_is_reproducible_with_lead_and_payload() {
+        let dir = test_dir("rpm");
+        let layout = vec![
+            (test_entry(&dir, "usr/bin/tool", "#!/bin/sh\necho hi\n", true), FileKind::Program),
+            (test_entry(&dir, "usr/share/licenses/tool/LICENSE", "MIT", false), FileKind::License),
+        ];
+        let (a, b) = (dir.join("a.rpm"), dir.join("b.rpm"));
+        write_rpm(&a, &metadata(), &layout).unwrap();
+        write_rpm(&b, &metadata(), &layout).unwrap();
+        let bytes = std::fs::read(&a).unwrap();
+        assert_eq!(bytes, std::fs::read(&b).unwrap());
+
+        assert_eq!(&bytes[..4], &[0xed, 0xab, 0xee, 0xdb]);
+        assert!(bytes[10..].starts_with(b"tool-1.2.3~rc.1-1\0"));
+
+        // signature header, padded to 8 bytes, then the main header and the payload
+        let sig_len = 16 + be32(&bytes, 96 + 8) as usize * 16 + be32(&bytes, 96 + 12) as usize;
+        let header_start = 96 + sig_len.next_multiple_of(8);
+        assert_eq!(&bytes[header_start..header_start + 3], &[0x8e, 0xad, 0xe8]);
+        let header_len = 16 + be32(&bytes, header_start + 8) as usize * 16 + be32(&bytes, header_start + 12) as usize;
+        let payload = &bytes[header_start + header_len..];
+
+        let mut cpio = Vec::new();
+        std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(payload), &mut cpio).unwrap();
+        let cpio = String::from_utf8_lossy(&cpio);
+        assert!(cpio.starts_with("070701"));
+        assert!(cpio.contains("./usr/bin/tool\0"));
+        assert!(cpio.contains("./usr/share/licenses/tool/LICENSE\0"));
+        assert!(cpio.contains("TRAILER!!!\0"));
+
+        let info = read_rpm(&a).unwrap();
+        assert_eq!((info.name.as_str(), info.version.as_str(), info.arch.as_str()), ("tool", "1.2.3~rc.1", "x86_64"));
+        assert_eq!(info.header_range, (header_start, header_start + header_len));
+        assert_eq!(info.build_time, 1_700_000_000);
+        assert_eq!(
+            info.files,
+            vec![("/usr/bin/tool".to_string(), false), ("/usr/share/licenses/tool/LICENSE".to_string(), false)]
+        );
+        assert_eq!(
+            info.requires,
+            vec![Dependency {
+                name: "glibc".to_string(),
+                flags: Some("GE"),
+                version: "2.28".to_string(),
+            }]
+        );
+        assert_eq!(info.provides[0].flags, Some("EQ"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
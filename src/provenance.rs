@@ -0,0 +1,180 @@
+//! SLSA provenance for each archive, as an unsigned in-toto statement in `<archive>.intoto.jsonl`.
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value, json};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::artifact::Artifact;
+
+const BUILD_TYPE: &str = "https://github.com/nakajima/releasor2000/provenance/v1";
+
+/// What every archive of a release was built from.
+pub struct BuildInfo<'a> {
+    pub repo: &'a str,
+    pub version: &'a str,
+    pub git_commit: Option<String>,
+    /// Output of `rustc -vV`
+    pub rustc: Option<&'a str>,
+    /// `[build] pre_built_dir`, when the binaries weren't built by releasor2000
+    pub pre_built_dir: Option<&'a str>,
+}
+
+pub fn git_commit() -> Option<String> {
+    Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+}
+
+/// `rustc -vV` as an object: the first line under `version`, then its `key: value` lines.
+fn rustc_fields(rustc: &str) -> Value {
+    let mut lines = rustc.lines();
+    let mut fields = Map::new();
+    if let Some(version) = lines.next() {
+        fields.insert("version".to_string(), json!(version.trim()));
+    }
+    for (key, value) in lines.filter_map(|line| line.split_once(": ")) {
+        fields.insert(key.trim().to_string(), json!(value.trim()));
+    }
+    Value::Object(fields)
+}
+
+/// The in-toto statement for `archive`, built by `commands` (after placeholder substitution).
+pub fn statement(archive: &Artifact, commands: &[String], info: &BuildInfo) -> Value {
+    let mut external = json!({
+        "repository": format!("https://github.com/{}", info.repo),
+        "version": info.version,
+        "target": archive.target,
+        "commands": commands,
+    });
+    if let Some(dir) = info.pre_built_dir {
+        external["preBuiltDir"] = json!(dir);
+    }
+    let mut internal = json!({});
+    if let Some(rustc) = info.rustc {
+        internal["rustc"] = rustc_fields(rustc);
+    }
+    let mut source = json!({ "uri": format!("git+https://github.com/{}@refs/tags/v{}", info.repo, info.version) });
+    if let Some(commit) = &info.git_commit {
+        source["digest"] = json!({ "gitCommit": commit });
+    }
+    json!({
+        "_type": "https://in-toto.io/Statement/v1",
+        "subject": [{ "name": archive.name(), "digest": { "sha256": archive.sha256() } }],
+        "predicateType": "https://slsa.dev/provenance/v1",
+        "predicate": {
+            "buildDefinition": {
+                "buildType": BUILD_TYPE,
+                "externalParameters": external,
+                "internalParameters": internal,
+                "resolvedDependencies": [source],
+            },
+            "runDetails": {
+                "builder": { "id": format!("https://github.com/nakajima/releasor2000@v{}", env!("CARGO_PKG_VERSION")) },
+            },
+        },
+    })
+}
+
+pub fn provenance_path(archive: &Path) -> PathBuf {
+    let mut path = archive.as_os_str().to_owned();
+    path.push(".intoto.jsonl");
+    PathBuf::from(path)
+}
+
+/// Writes the statement for `archive` next to it, one JSON object on one line.
+pub fn write_provenance(archive: &Artifact, commands: &[String], info: &BuildInfo) -> Result<PathBuf> {
+    let path = provenance_path(&archive.path);
+    let line = format!("{}\n", statement(archive, commands, info));
+    std::fs::write(&path, line).with_context(|| format!("writing {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUSTC: &str = "rustc 1.88.0 (6b00bc388 2025-06-23)\nbinary: rustc\ncommit-hash: 6b00bc3880198600130e1cf62b8f8a93494488cc\nhost: x86_64-unknown-linux-gnu\nrelease: 1.88.0\nLLVM version: 20.1.5\n";
+
+    fn archive() -> Artifact {
+        Artifact {
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            path: PathBuf::from("target/release-staging/tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz"),
+            format: Default::default(),
+            files: Vec::new(),
+            digests: crate::artifact::Digests {
+                sha256: "abc123".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn info() -> BuildInfo<'static> {
+        BuildInfo {
+            repo: "owner/tool",
+            version: "1.0.0",
+            git_commit: Some("0123abcd".to_string()),
+            rustc: Some(RUSTC),
+            pre_built_dir: None,
+        }
+    }
+
+    // --- statement tests ---
+
+    #[test]
+    fn statement_names_the_archive_as_subject() {
+        let commands = vec!["cargo build --release --target x86_64-unknown-linux-gnu".to_string()];
+        let statement = statement(&archive(), &commands, &info());
+        assert_eq!(statement["_type"], "https://in-toto.io/Statement/v1");
+        assert_eq!(statement["predicateType"], "https://slsa.dev/provenance/v1");
+        assert_eq!(
+            statement["subject"],
+            json!([{ "name": "tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", "digest": { "sha256": "abc123" } }])
+        );
+        let build = &statement["predicate"]["buildDefinition"];
+        assert_eq!(build["externalParameters"]["commands"], json!(commands));
+        assert_eq!(build["externalParameters"]["target"], "x86_64-unknown-linux-gnu");
+        assert!(build["externalParameters"].get("preBuiltDir").is_none());
+        assert_eq!(
+            build["resolvedDependencies"],
+            json!([{ "uri": "git+https://github.com/owner/tool@refs/tags/v1.0.0", "digest": { "gitCommit": "0123abcd" } }])
+        );
+        let builder = statement["predicate"]["runDetails"]["builder"]["id"].as_str().unwrap();
+        assert!(builder.starts_with("https://github.com/nakajima/releasor2000@v"));
+    }
+
+    #[test]
+    fn statement_records_rustc_version() {
+        let rustc = &statement(&archive(), &[], &info())["predicate"]["buildDefinition"]["internalParameters"]["rustc"];
+        assert_eq!(rustc["version"], "rustc 1.88.0 (6b00bc388 2025-06-23)");
+        assert_eq!(rustc["release"], "1.88.0");
+        assert_eq!(rustc["commit-hash"], "6b00bc3880198600130e1cf62b8f8a93494488cc");
+        assert_eq!(rustc["LLVM version"], "20.1.5");
+    }
+
+    #[test]
+    fn statement_for_pre_built_binaries() {
+        let info = BuildInfo {
+            git_commit: None,
+            rustc: None,
+            pre_built_dir: Some("dist"),
+            ..info()
+        };
+        let build = &statement(&archive(), &[], &info)["predicate"]["buildDefinition"];
+        assert_eq!(build["externalParameters"]["preBuiltDir"], "dist");
+        assert_eq!(build["externalParameters"]["commands"], json!([]));
+        assert_eq!(build["internalParameters"], json!({}));
+        assert!(build["resolvedDependencies"][0].get("digest").is_none());
+    }
+
+    #[test]
+    fn provenance_path_appends_intoto_jsonl() {
+        assert_eq!(
+            provenance_path(Path::new("target/release-staging/tool.tar.gz")),
+            Path::new("target/release-staging/tool.tar.gz.intoto.jsonl")
+        );
+    }
+}